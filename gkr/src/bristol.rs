@@ -0,0 +1,297 @@
+use crate::builder::{CircuitBuilder, Wire};
+use crate::circuit::file::{LoadCircuitError, ParseCircuitError};
+use crate::circuit::CircuitDescription;
+use ark_ff::PrimeField;
+use std::{fs, path::Path};
+
+// Imports a boolean circuit in Bristol Fashion:
+//
+//     <gates> <wires>
+//     <number of inputs> <bits of input 1> <bits of input 2> ...
+//     <number of outputs> <bits of output 1> ...
+//     <in> <out> <input wires> <output wires> <XOR|AND|INV|EQW|EQ|MAND>
+//
+// The input bits are wires 0, 1, ... and the output bits the last wires, both in order.
+// Gates become arithmetic over F that agrees with the boolean gate on 0 and 1: AND is ab,
+// INV is 1 - a and XOR is a + b - 2ab, built as (a - b)^2 to save a layer. EQW copies a wire,
+// EQ sets it to the constant 0 or 1 and MAND is a batch of ANDs. The builder places every
+// gate by its depth and relays values that skip layers.
+pub fn import<F: PrimeField>(input: &str) -> Result<CircuitDescription<F>, ParseCircuitError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.split_whitespace().collect::<Vec<&str>>()))
+        .filter(|(_, fields)| !fields.is_empty());
+
+    let mut header = || {
+        let (line, fields) = lines.next().ok_or_else(|| ParseCircuitError {
+            line: input.lines().count(),
+            reason: "incomplete header".into(),
+        })?;
+        let numbers = fields
+            .iter()
+            .map(|field| field.parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| ParseCircuitError {
+                line,
+                reason: "invalid number in header".into(),
+            })?;
+        Ok::<_, ParseCircuitError>((line, numbers))
+    };
+
+    let (line, counts) = header()?;
+    if counts.len() != 2 {
+        return Err(ParseCircuitError {
+            line,
+            reason: "expected the number of gates and wires".into(),
+        });
+    }
+    let (no_of_gates, no_of_wires) = (counts[0], counts[1]);
+    let no_of_inputs = bit_count(header()?)?;
+    let no_of_outputs = bit_count(header()?)?;
+    if no_of_inputs
+        .checked_add(no_of_outputs)
+        .is_none_or(|bits| bits > no_of_wires)
+    {
+        return Err(ParseCircuitError {
+            line,
+            reason: "more input and output bits than wires".into(),
+        });
+    }
+    // every wire past the inputs is named as a gate output somewhere in the file, so a larger
+    // count can't be right and would only make the wire table huge
+    if no_of_wires - no_of_inputs > input.split_whitespace().count() {
+        return Err(ParseCircuitError {
+            line,
+            reason: format!("{no_of_wires} wires is more than the gates can set"),
+        });
+    }
+
+    let mut builder = CircuitBuilder::new();
+    let mut wires: Vec<Option<Wire>> = vec![None; no_of_wires];
+    for wire in wires.iter_mut().take(no_of_inputs) {
+        *wire = Some(builder.input());
+    }
+
+    let mut gates_read = 0;
+    for (line, fields) in lines {
+        let error = |reason: String| ParseCircuitError { line, reason };
+        gates_read += 1;
+        if gates_read > no_of_gates {
+            return Err(error(format!("more than {no_of_gates} gates")));
+        }
+
+        let (op, counts) = fields.split_last().unwrap();
+        let numbers = counts
+            .iter()
+            .map(|field| field.parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| error("invalid number".into()))?;
+        let listed = numbers
+            .get(..2)
+            .and_then(|arity| arity[0].checked_add(arity[1]));
+        if listed.and_then(|listed| listed.checked_add(2)) != Some(numbers.len()) {
+            return Err(error("wire count doesn't match the wires listed".into()));
+        }
+        let (arity, rest) = (&numbers[..2], &numbers[2..]);
+        let (input_wires, output_wires) = rest.split_at(arity[0]);
+
+        let expect_arity = |inputs: usize, outputs: usize| {
+            if arity == [inputs, outputs] {
+                Ok(())
+            } else {
+                Err(error(format!(
+                    "{op} takes {inputs} inputs and {outputs} outputs"
+                )))
+            }
+        };
+        let read = |index: usize| match wires.get(index) {
+            Some(Some(wire)) => Ok(*wire),
+            Some(None) => Err(error(format!("wire {index} is read before it is set"))),
+            None => Err(error(format!("wire {index} is out of range"))),
+        };
+
+        let results = match *op {
+            "XOR" => {
+                expect_arity(2, 1)?;
+                let difference = builder.sub(read(input_wires[0])?, read(input_wires[1])?);
+                vec![builder.mul(difference, difference)]
+            }
+            "AND" => {
+                expect_arity(2, 1)?;
+                vec![builder.mul(read(input_wires[0])?, read(input_wires[1])?)]
+            }
+            "INV" => {
+                expect_arity(1, 1)?;
+                let one = builder.constant(F::one());
+                vec![builder.sub(one, read(input_wires[0])?)]
+            }
+            "EQW" => {
+                expect_arity(1, 1)?;
+                vec![read(input_wires[0])?]
+            }
+            "EQ" => {
+                expect_arity(1, 1)?;
+                match input_wires[0] {
+                    0 => vec![builder.constant(F::zero())],
+                    1 => vec![builder.constant(F::one())],
+                    _ => return Err(error("EQ takes a constant 0 or 1".into())),
+                }
+            }
+            "MAND" => {
+                let half = output_wires.len();
+                if input_wires.len() != 2 * half {
+                    return Err(error("MAND takes two inputs per output".into()));
+                }
+                let mut results = Vec::with_capacity(half);
+                for i in 0..half {
+                    results.push(builder.mul(read(input_wires[i])?, read(input_wires[half + i])?));
+                }
+                results
+            }
+            _ => return Err(error(format!("unknown gate {op}"))),
+        };
+
+        for (index, result) in output_wires.iter().zip(results) {
+            match wires.get_mut(*index) {
+                Some(wire @ None) => *wire = Some(result),
+                Some(Some(_)) => return Err(error(format!("wire {index} is set twice"))),
+                None => return Err(error(format!("wire {index} is out of range"))),
+            }
+        }
+    }
+
+    if gates_read != no_of_gates {
+        return Err(ParseCircuitError {
+            line: input.lines().count(),
+            reason: format!("expected {no_of_gates} gates, found {gates_read}"),
+        });
+    }
+
+    let mut outputs = Vec::with_capacity(no_of_outputs);
+    for (index, wire) in wires.iter().enumerate().skip(no_of_wires - no_of_outputs) {
+        let mut wire = wire.ok_or_else(|| ParseCircuitError {
+            line: input.lines().count(),
+            reason: format!("output wire {index} is never set"),
+        })?;
+        // EQW can make two output wires the same value, which the builder needs as two gates
+        if outputs.contains(&wire) {
+            wire = builder.mul_constant(wire, F::one());
+        }
+        outputs.push(wire);
+        builder.mark_output(wire);
+    }
+    Ok(builder.build())
+}
+
+pub fn load<F: PrimeField>(
+    path: impl AsRef<Path>,
+) -> Result<CircuitDescription<F>, LoadCircuitError> {
+    let input = fs::read_to_string(path).map_err(LoadCircuitError::Io)?;
+    import(&input).map_err(LoadCircuitError::Parse)
+}
+
+// `<count> <bits> <bits> ...` to the total number of bits
+fn bit_count((line, numbers): (usize, Vec<usize>)) -> Result<usize, ParseCircuitError> {
+    match numbers.split_first() {
+        Some((count, bits)) if *count == bits.len() => bits
+            .iter()
+            .try_fold(0usize, |total, bits| total.checked_add(*bits))
+            .ok_or_else(|| ParseCircuitError {
+                line,
+                reason: "too many bits".into(),
+            }),
+        _ => Err(ParseCircuitError {
+            line,
+            reason: "bit widths don't match their count".into(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gkr::{prove, verify};
+    use ark_bn254::Fq;
+
+    fn to_field(input: Vec<u64>) -> Vec<Fq> {
+        input.into_iter().map(Fq::from).collect()
+    }
+
+    // sum and carry of a + b + carry_in
+    const FULL_ADDER: &str = "\
+5 8
+3 1 1 1
+2 1 1
+
+2 1 0 1 3 XOR
+2 1 0 1 4 AND
+2 1 2 3 5 AND
+2 1 3 2 6 XOR
+2 1 4 5 7 XOR
+";
+
+    #[test]
+    fn test_full_adder() {
+        let circuit = import::<Fq>(FULL_ADDER).unwrap();
+        for bits in 0..8u64 {
+            let inputs = to_field(vec![bits & 1, (bits >> 1) & 1, bits >> 2]);
+            let total = (bits & 1) + ((bits >> 1) & 1) + (bits >> 2);
+            assert_eq!(
+                circuit.evaluate(&inputs).outputs(),
+                to_field(vec![total & 1, total >> 1])
+            );
+        }
+
+        let inputs = to_field(vec![1, 1, 0]);
+        let proof = prove(&circuit, &inputs);
+        assert!(verify(&circuit, &inputs, &proof).is_ok());
+    }
+
+    #[test]
+    fn test_inv_eq_and_mand() {
+        // out = (a AND 1, NOT a AND b, the same copied)
+        let input = "\
+5 8
+2 1 1
+1 3
+1 1 0 2 INV
+1 1 1 3 EQ
+4 2 2 0 1 3 4 5 MAND
+1 1 4 6 EQW
+1 1 6 7 EQW
+";
+        let circuit = import::<Fq>(input).unwrap();
+        for (a, b) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+            let inputs = to_field(vec![a, b]);
+            assert_eq!(
+                circuit.evaluate(&inputs).outputs(),
+                to_field(vec![a, (1 - a) * b, (1 - a) * b])
+            );
+        }
+    }
+
+    #[test]
+    fn test_import_reports_line() {
+        let error = import::<Fq>("2 4\n2 1 1\n1 1\n\n2 1 0 1 2 AND\n2 1 0 5 3 XOR\n");
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "line 6: wire 5 is out of range"
+        );
+
+        let error = import::<Fq>("1 4\n2 1 1\n1 1\n2 1 0 2 3 AND\n").unwrap_err();
+        assert_eq!(error.line, 4);
+        let error = import::<Fq>("1 3\n2 1 1\n1 1\n2 1 0 1 2 NAND\n").unwrap_err();
+        assert_eq!(error.line, 4);
+        let error = import::<Fq>("1 3\n2 1 1\n1 1\n2 1 0 1 2 AND\n2 1 0 1 2 AND\n").unwrap_err();
+        assert_eq!(error.line, 5);
+
+        // header counts that no file of this size could back
+        let error = import::<Fq>("1 99999999999999999\n2 1 1\n1 1\n2 1 0 1 2 AND\n").unwrap_err();
+        assert_eq!(error.line, 1);
+        let error = import::<Fq>(&format!("1 3\n2 {} 1\n1 1\n2 1 0 1 2 AND\n", usize::MAX));
+        assert_eq!(error.unwrap_err().line, 2);
+        let error = import::<Fq>(&format!("1 3\n2 1 1\n1 1\n{} 1 0 1 2 AND\n", usize::MAX));
+        assert_eq!(error.unwrap_err().line, 4);
+    }
+}
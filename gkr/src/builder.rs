@@ -0,0 +1,294 @@
+use crate::circuit::{CircuitDescription, Gate, Layer, Op};
+use ark_ff::PrimeField;
+use std::collections::HashMap;
+
+// A value in a circuit under construction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Wire(usize);
+
+#[derive(Debug, Clone)]
+enum Node<F: PrimeField> {
+    Input,
+    Constant(F),
+    Gate(Op<F>, Wire, Wire),
+}
+
+// Builds a layered Circuit from wires combined with add/mul/sub instead of hand numbered gates.
+// Every wire is placed on the layer given by its distance from the inputs and operands coming
+// from further down are carried up with relay gates. Constants are constant gates on whatever
+// layer needs them, or extra input wires after the inputs when a first layer gate reads them.
+#[derive(Debug, Clone)]
+pub struct CircuitBuilder<F: PrimeField> {
+    nodes: Vec<Node<F>>,
+    // distance from the input layer
+    depths: Vec<usize>,
+    outputs: Vec<Wire>,
+}
+
+impl<F: PrimeField> Default for CircuitBuilder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            depths: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    // inputs are numbered in the order they are created, which is the order the built circuit
+    // expects their values in
+    pub fn input(&mut self) -> Wire {
+        self.push(Node::Input, 0)
+    }
+
+    pub fn constant(&mut self, value: F) -> Wire {
+        let existing = self
+            .nodes
+            .iter()
+            .position(|node| matches!(node, Node::Constant(constant) if *constant == value));
+        match existing {
+            Some(index) => Wire(index),
+            None => self.push(Node::Constant(value), 0),
+        }
+    }
+
+    pub fn add(&mut self, left: Wire, right: Wire) -> Wire {
+        self.gate(Op::Add, left, right)
+    }
+
+    pub fn mul(&mut self, left: Wire, right: Wire) -> Wire {
+        self.gate(Op::Mul, left, right)
+    }
+
+    pub fn sub(&mut self, left: Wire, right: Wire) -> Wire {
+        self.gate(Op::Sub, left, right)
+    }
+
+    pub fn mul_constant(&mut self, wire: Wire, constant: F) -> Wire {
+        self.gate(Op::ConstMul(constant), wire, wire)
+    }
+
+    pub fn mark_output(&mut self, wire: Wire) {
+        assert!(
+            !self.outputs.contains(&wire),
+            "wire is already marked as an output"
+        );
+        self.outputs.push(wire);
+    }
+
+    pub fn no_of_inputs(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| matches!(node, Node::Input))
+            .count()
+    }
+
+    pub fn build(&self) -> CircuitDescription<F> {
+        assert!(!self.outputs.is_empty(), "circuit needs an output wire");
+
+        let output_depth = self.outputs.iter().map(|wire| self.depths[wire.0]).max();
+        let depth = output_depth.unwrap().max(1);
+        let levels = self.levels(depth);
+
+        let indices: Vec<HashMap<Wire, usize>> = levels
+            .iter()
+            .map(|wires| wires.iter().enumerate().map(|(i, w)| (*w, i)).collect())
+            .collect();
+
+        let mut layers = Vec::with_capacity(depth);
+        for level in (1..=depth).rev() {
+            let below = &indices[level - 1];
+            let gates = levels[level]
+                .iter()
+                .enumerate()
+                .map(|(output, wire)| match self.nodes[wire.0] {
+                    Node::Gate(op, left, right) if self.depths[wire.0] == level => {
+                        Gate::new(op, below[&left], below[&right], output)
+                    }
+                    Node::Constant(value) => Gate::new(Op::Const(value), 0, 0, output),
+                    _ => Gate::new(Op::Relay, below[wire], below[wire], output),
+                })
+                .collect();
+            layers.push(Layer::init(gates));
+        }
+
+        let constant_inputs = levels[0]
+            .iter()
+            .filter_map(|wire| match self.nodes[wire.0] {
+                Node::Constant(value) => Some(value),
+                _ => None,
+            })
+            .collect();
+
+        CircuitDescription::with_constant_inputs(layers, self.no_of_inputs(), constant_inputs)
+    }
+
+    fn push(&mut self, node: Node<F>, depth: usize) -> Wire {
+        self.nodes.push(node);
+        self.depths.push(depth);
+        Wire(self.nodes.len() - 1)
+    }
+
+    fn gate(&mut self, op: Op<F>, left: Wire, right: Wire) -> Wire {
+        let depth = self.depths[left.0].max(self.depths[right.0]) + 1;
+        self.push(Node::Gate(op, left, right), depth)
+    }
+
+    // The wires on every level, from the inputs (level 0) up to the outputs (level `depth`).
+    // Level 0 lists all inputs in creation order followed by the constants read from it.
+    fn levels(&self, depth: usize) -> Vec<Vec<Wire>> {
+        let mut levels = vec![Vec::new(); depth + 1];
+        levels[depth] = self.outputs.clone();
+
+        for level in (1..=depth).rev() {
+            let mut below = Vec::new();
+            for wire in &levels[level] {
+                match self.nodes[wire.0] {
+                    Node::Gate(_, left, right) if self.depths[wire.0] == level => {
+                        below.extend([left, right])
+                    }
+                    // a constant gate needs nothing from below
+                    Node::Constant(_) => {}
+                    _ => below.push(*wire),
+                }
+            }
+
+            let mut seen = std::collections::HashSet::new();
+            below.retain(|wire| seen.insert(*wire));
+            levels[level - 1] = below;
+        }
+
+        let inputs = (0..self.nodes.len())
+            .filter(|i| matches!(self.nodes[*i], Node::Input))
+            .map(Wire);
+        let constants: Vec<Wire> = levels[0]
+            .iter()
+            .filter(|wire| matches!(self.nodes[wire.0], Node::Constant(_)))
+            .copied()
+            .collect();
+        levels[0] = inputs.chain(constants).collect();
+
+        levels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gkr::{prove, verify};
+    use ark_bn254::Fq;
+
+    fn to_field(input: Vec<u64>) -> Vec<Fq> {
+        input.into_iter().map(Fq::from).collect()
+    }
+
+    #[test]
+    fn test_build_and_prove() {
+        // (x0 + x1) * (x2 - 3)
+        let mut builder = CircuitBuilder::new();
+        let x = [builder.input(), builder.input(), builder.input()];
+        let sum = builder.add(x[0], x[1]);
+        let three = builder.constant(Fq::from(3));
+        let difference = builder.sub(x[2], three);
+        let output = builder.mul(sum, difference);
+        builder.mark_output(output);
+
+        let inputs = to_field(vec![1, 2, 10]);
+        let circuit = builder.build();
+        assert_eq!(circuit.evaluate(&inputs).outputs(), to_field(vec![21]));
+
+        let proof = prove(&circuit, &inputs);
+        assert!(verify(&circuit, &inputs, &proof).is_ok());
+    }
+
+    #[test]
+    fn test_wires_skip_layers() {
+        // x0 * x1 * x2 * x3 + x4: x4 has to be relayed up two layers
+        let mut builder = CircuitBuilder::new();
+        let x: Vec<Wire> = (0..5).map(|_| builder.input()).collect();
+        let left = builder.mul(x[0], x[1]);
+        let right = builder.mul(x[2], x[3]);
+        let product = builder.mul(left, right);
+        let output = builder.add(product, x[4]);
+        builder.mark_output(output);
+
+        let inputs = to_field(vec![2, 3, 4, 5, 6]);
+        let circuit = builder.build();
+        assert_eq!(circuit.evaluate(&inputs).outputs(), to_field(vec![126]));
+
+        let proof = prove(&circuit, &inputs);
+        assert!(verify(&circuit, &inputs, &proof).is_ok());
+    }
+
+    #[test]
+    fn test_two_outputs() {
+        // 5 * (x0 - x1) and x0 * x1 + 7
+        let mut builder = CircuitBuilder::new();
+        let x = [builder.input(), builder.input()];
+        let difference = builder.sub(x[0], x[1]);
+        let scaled = builder.mul_constant(difference, Fq::from(5));
+        let product = builder.mul(x[0], x[1]);
+        let seven = builder.constant(Fq::from(7));
+        let shifted = builder.add(product, seven);
+        builder.mark_output(scaled);
+        builder.mark_output(shifted);
+
+        let inputs = to_field(vec![9, 4]);
+        let circuit = builder.build();
+        assert_eq!(circuit.evaluate(&inputs).outputs(), to_field(vec![25, 43]));
+
+        let proof = prove(&circuit, &inputs);
+        assert!(verify(&circuit, &inputs, &proof).is_ok());
+    }
+
+    #[test]
+    fn test_odd_widths() {
+        // three outputs over five inputs, none of the layers a power of two wide
+        let mut builder = CircuitBuilder::new();
+        let x: Vec<Wire> = (0..5).map(|_| builder.input()).collect();
+        let sum = builder.add(x[0], x[1]);
+        let product = builder.mul(x[2], x[3]);
+        let difference = builder.sub(sum, x[4]);
+        let combined = builder.mul(sum, product);
+        builder.mark_output(difference);
+        builder.mark_output(combined);
+        builder.mark_output(x[4]);
+
+        let mut inputs = to_field(vec![1, 2, 3, 4, 5]);
+        let circuit = builder.build();
+        let proof = prove(&circuit, &inputs);
+        assert_eq!(proof.outputs, vec![-Fq::from(2), Fq::from(36), Fq::from(5)]);
+        assert!(verify(&circuit, &inputs, &proof).is_ok());
+
+        inputs[4] += Fq::from(1);
+        assert!(verify(&circuit, &inputs, &proof).is_err());
+    }
+
+    #[test]
+    fn test_inner_product() {
+        let mut builder = CircuitBuilder::new();
+        let a: Vec<Wire> = (0..32).map(|_| builder.input()).collect();
+        let b: Vec<Wire> = (0..32).map(|_| builder.input()).collect();
+        let mut terms: Vec<Wire> = a.iter().zip(&b).map(|(a, b)| builder.mul(*a, *b)).collect();
+        while terms.len() > 1 {
+            terms = terms
+                .chunks(2)
+                .map(|pair| builder.add(pair[0], pair[1]))
+                .collect();
+        }
+        builder.mark_output(terms[0]);
+
+        let values: Vec<u64> = (1..=32).chain(1..=32).collect();
+        let circuit = builder.build();
+        let expected: u64 = (1..=32).map(|i| i * i).sum();
+        assert_eq!(
+            circuit.evaluate(&to_field(values)).outputs(),
+            to_field(vec![expected])
+        );
+    }
+}
@@ -1,21 +1,48 @@
 use ark_ff::PrimeField;
-use multivariate_poly::{
-    add_polynomials, product_poly::ProductPoly, sum_poly::SumPoly, tensor_add, tensor_mul,
-    MultilinearPolynomial,
-};
+use multivariate_poly::MultilinearPolynomial;
+#[cfg(test)]
+use multivariate_poly::{product_poly::ProductPoly, sum_poly::SumPoly, tensor_add, tensor_mul};
+
+pub mod file;
+
+// Relay and ConstMul only read the left input and Const reads neither; the unused indices
+// still pick the point where the gate sits in its wiring predicate, so any wire in range will do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op<F: PrimeField> {
+    Add,
+    Mul,
+    Sub,
+    Relay,
+    ConstMul(F),
+    Const(F),
+}
+
+pub const NO_OF_PREDICATES: usize = 6;
+
+impl<F: PrimeField> Op<F> {
+    // which wiring predicate the gate belongs to, and the value it takes there
+    fn predicate(&self) -> (usize, F) {
+        match self {
+            Op::Add => (0, F::one()),
+            Op::Mul => (1, F::one()),
+            Op::Sub => (2, F::one()),
+            Op::Relay => (3, F::one()),
+            Op::ConstMul(constant) => (4, *constant),
+            Op::Const(constant) => (5, *constant),
+        }
+    }
+}
 
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
-pub struct Gate {
-    op: char,
+pub struct Gate<F: PrimeField> {
+    op: Op<F>,
     left: usize,
     right: usize,
     output: usize,
 }
 
-#[allow(dead_code)]
-impl Gate {
-    pub fn new(op: char, left: usize, right: usize, output: usize) -> Self {
+impl<F: PrimeField> Gate<F> {
+    pub fn new(op: Op<F>, left: usize, right: usize, output: usize) -> Self {
         Self {
             op,
             left,
@@ -24,153 +51,344 @@ impl Gate {
         }
     }
 
-    fn operate<F: PrimeField>(&mut self, inputs: Vec<F>) -> F {
+    fn operate(&self, inputs: &[F]) -> F {
         let a = inputs[self.left];
         let b = inputs[self.right];
         // self.output = output_index;
 
         match self.op {
-            '+' => a + b,
-            '*' => a * b,
-            _ => panic!("Invalid operation"),
+            Op::Add => a + b,
+            Op::Mul => a * b,
+            Op::Sub => a - b,
+            Op::Relay => a,
+            Op::ConstMul(constant) => constant * a,
+            Op::Const(constant) => constant,
         }
     }
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct Layer<F: PrimeField> {
-    gates: Vec<Gate>,
-    outputs: Vec<F>,
+    gates: Vec<Gate<F>>,
 }
 
-#[allow(dead_code)]
 impl<F: PrimeField> Layer<F> {
-    pub fn init(gates: Vec<Gate>) -> Self {
-        Self {
-            gates,
-            outputs: vec![],
-        }
+    pub fn init(gates: Vec<Gate<F>>) -> Self {
+        Self { gates }
     }
 
-    fn compute(&mut self, inputs: Vec<F>) -> Vec<F> {
-        for gate in self.gates.iter_mut() {
-            let output = gate.operate(inputs.clone());
-            self.outputs.push(output);
-        }
+    pub fn no_of_gates(&self) -> usize {
+        self.gates.len()
+    }
 
-        self.outputs.clone()
+    fn compute(&self, inputs: &[F]) -> Vec<F> {
+        self.gates.iter().map(|gate| gate.operate(inputs)).collect()
     }
 }
 
-#[allow(dead_code)]
+// The structure of a layered circuit, layers listed output first. It holds no values, so one
+// description can be evaluated on any number of inputs. The input layer is the public inputs
+// followed by `constant_inputs`, fixed values that belong to the circuit itself.
 #[derive(Debug, Clone)]
-pub struct Circuit<F: PrimeField> {
-    pub inputs: Vec<F>,
+pub struct CircuitDescription<F: PrimeField> {
     pub layers: Vec<Layer<F>>,
-    pub outputs: Vec<Vec<F>>,
+    pub no_of_inputs: usize,
+    pub constant_inputs: Vec<F>,
+}
+
+// The values on every layer of a circuit for one set of inputs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Witness<F: PrimeField> {
+    // the whole input layer, constant inputs included
+    pub inputs: Vec<F>,
+    // output layer first, like CircuitDescription::layers
+    pub layers: Vec<Vec<F>>,
 }
 
-#[allow(dead_code)]
-impl<F: PrimeField> Circuit<F> {
-    pub fn create(inputs: Vec<F>, layers: Vec<Layer<F>>) -> Self {
+impl<F: PrimeField> Witness<F> {
+    pub fn outputs(&self) -> &[F] {
+        &self.layers[0]
+    }
+
+    pub fn w_i_polynomial(&self, layer_index: usize) -> MultilinearPolynomial<F> {
+        assert!(layer_index <= self.layers.len(), "layer doesn't exist");
+        if layer_index == self.layers.len() {
+            padded_polynomial(self.inputs.clone())
+        } else {
+            padded_polynomial(self.layers[layer_index].clone())
+        }
+    }
+}
+
+impl<F: PrimeField> CircuitDescription<F> {
+    pub fn new(layers: Vec<Layer<F>>, no_of_inputs: usize) -> Self {
+        Self::with_constant_inputs(layers, no_of_inputs, Vec::new())
+    }
+
+    pub fn with_constant_inputs(
+        layers: Vec<Layer<F>>,
+        no_of_inputs: usize,
+        constant_inputs: Vec<F>,
+    ) -> Self {
+        // every wire a gate reads has to exist on the layer below it, Const gates included since
+        // their indices still place them in the wiring predicates; a gate's output is its
+        // position in the layer, which the predicates and the evaluation both go by
+        for (layer_index, layer) in layers.iter().enumerate() {
+            let below = match layers.get(layer_index + 1) {
+                Some(layer) => layer.no_of_gates(),
+                None => no_of_inputs + constant_inputs.len(),
+            };
+            for (gate_index, gate) in layer.gates.iter().enumerate() {
+                assert!(
+                    gate.left < below && gate.right < below,
+                    "gate {gate_index} of layer {layer_index} reads wires {} and {}, \
+                     but the layer below has {below}",
+                    gate.left,
+                    gate.right
+                );
+                assert_eq!(
+                    gate.output, gate_index,
+                    "gate {gate_index} of layer {layer_index} claims to output wire {}",
+                    gate.output
+                );
+            }
+        }
+
         Self {
-            inputs,
             layers,
-            outputs: Vec::new(),
+            no_of_inputs,
+            constant_inputs,
         }
     }
 
-    pub fn execute(&mut self) -> Vec<Vec<F>> {
-        let mut inputs = self.inputs.clone();
-
-        for layer in self.layers.iter_mut().rev() {
-            inputs = layer.compute(inputs);
-            self.outputs.insert(0, inputs.clone());
+    pub fn evaluate(&self, inputs: &[F]) -> Witness<F> {
+        let inputs = self.input_layer(inputs);
+        let mut layers = Vec::with_capacity(self.layers.len());
+        let mut current = inputs.clone();
+        for layer in self.layers.iter().rev() {
+            current = layer.compute(&current);
+            layers.push(current.clone());
         }
+        layers.reverse();
 
-        self.outputs.clone()
+        Witness { inputs, layers }
     }
 
-    pub fn w_i_polynomial(&self, layer_index: usize) -> MultilinearPolynomial<F> {
-        assert!(layer_index <= self.outputs.len(), "layer doesn't exist");
-        if layer_index == self.outputs.len() {
-            let layer_coeffs = self.inputs.clone();
-            return MultilinearPolynomial::new(layer_coeffs);
+    // the public inputs followed by the constant inputs
+    pub fn input_layer(&self, inputs: &[F]) -> Vec<F> {
+        assert_eq!(
+            inputs.len(),
+            self.no_of_inputs,
+            "expected one value per input wire"
+        );
+        inputs
+            .iter()
+            .chain(self.constant_inputs.iter())
+            .copied()
+            .collect()
+    }
+
+    // W of the input layer, which is all the verifier needs to know about the inputs
+    pub fn input_polynomial(&self, inputs: &[F]) -> MultilinearPolynomial<F> {
+        padded_polynomial(self.input_layer(inputs))
+    }
+
+    // The same circuit with the constant inputs moved out of the input layer: a new layer above
+    // it relays the public inputs and produces each constant with a Const gate, keeping every
+    // wire index the old last layer reads. The input layer is then only the public inputs,
+    // which is what gets committed when the inputs stay with the prover.
+    pub fn with_constants_as_gates(&self) -> Self {
+        if self.constant_inputs.is_empty() {
+            return self.clone();
+        }
+        let relays = (0..self.no_of_inputs).map(|index| Gate::new(Op::Relay, index, index, index));
+        let constants = self
+            .constant_inputs
+            .iter()
+            .enumerate()
+            .map(|(i, constant)| Gate::new(Op::Const(*constant), 0, 0, self.no_of_inputs + i));
+        let mut layers = self.layers.clone();
+        layers.push(Layer::init(relays.chain(constants).collect()));
+        Self::new(layers, self.no_of_inputs)
+    }
+
+    // Layer widths don't have to be powers of two: every layer is padded with zero gates up to
+    // 2^layer_bits, so W_i is zero past the last gate. layer_index == layers.len() is the input
+    // layer.
+    pub fn layer_bits(&self, layer_index: usize) -> usize {
+        if layer_index == self.layers.len() {
+            width_bits(self.no_of_inputs + self.constant_inputs.len())
         } else {
-            let layer_coeffs = self.outputs[layer_index].clone();
-            MultilinearPolynomial::new(layer_coeffs)
+            width_bits(self.layers[layer_index].gates.len())
         }
     }
 
-    pub fn add_i_n_mul_i_arrays(
+    // Every wiring predicate at (r_a, r_b, r_c), summed over the layer's gates instead of
+    // evaluating the dense predicates: O(#gates * log width) and no 2^(3k) tables.
+    pub fn evaluate_wiring_predicates(
         &self,
         layer_index: usize,
-    ) -> (MultilinearPolynomial<F>, MultilinearPolynomial<F>) {
-        let index_combinations = gate_index_combinations(layer_index);
-        let boolean_combination = 1 << index_combinations;
+        r_a: &[F],
+        r_b: &[F],
+        r_c: &[F],
+    ) -> Vec<F> {
+        let mut predicates = vec![F::zero(); NO_OF_PREDICATES];
+        for gate in self.layers[layer_index].gates.iter() {
+            let (predicate, value) = gate.op.predicate();
+            predicates[predicate] +=
+                value * eq_at(r_a, gate.output) * eq_at(r_b, gate.left) * eq_at(r_c, gate.right);
+        }
+        predicates
+    }
 
-        let mut add_i_values = vec![F::zero(); boolean_combination];
-        let mut mul_i_values = vec![F::zero(); boolean_combination];
+    // sum over the layer's gates of output_weights[gate] * gate(w): the layer polynomial
+    // summed over the boolean (b, c), with the output variables already folded in
+    pub fn weighted_layer_sum(&self, layer_index: usize, w: &[F], output_weights: &[F]) -> F {
+        self.layers[layer_index]
+            .gates
+            .iter()
+            .map(|gate| output_weights[gate.output] * gate.operate(w))
+            .sum()
+    }
 
+    // Phase one of the linear time (Libra) prover. With the output variables summed out
+    // against `output_weights`, sum_c f(b, c) = W(b) * w_b_terms(b) + constant_terms(b), both
+    // tables built in one pass over the gates. `w` is W_{i+1} padded to 2^layer_bits.
+    pub fn phase_one_tables(
+        &self,
+        layer_index: usize,
+        w: &[F],
+        output_weights: &[F],
+    ) -> (Vec<F>, Vec<F>) {
+        let size = 1 << self.layer_bits(layer_index + 1);
+        let mut w_b_terms = vec![F::zero(); size];
+        let mut constant_terms = vec![F::zero(); size];
         for gate in self.layers[layer_index].gates.iter() {
+            let (_, value) = gate.op.predicate();
+            let weight = value * output_weights[gate.output];
+            let (left, w_c) = (gate.left, w[gate.right]);
             match gate.op {
-                '+' => {
-                    let valid_index =
-                        arrange_gate_index(layer_index, gate.output, gate.left, gate.right);
-                    add_i_values[valid_index] = F::one();
+                Op::Add => {
+                    w_b_terms[left] += weight;
+                    constant_terms[left] += weight * w_c;
                 }
-                '*' => {
-                    let valid_index =
-                        arrange_gate_index(layer_index, gate.output, gate.left, gate.right);
-                    mul_i_values[valid_index] = F::one();
+                Op::Mul => w_b_terms[left] += weight * w_c,
+                Op::Sub => {
+                    w_b_terms[left] += weight;
+                    constant_terms[left] -= weight * w_c;
                 }
-                _ => panic!("Invalid operation"),
+                Op::Relay | Op::ConstMul(_) => w_b_terms[left] += weight,
+                Op::Const(_) => constant_terms[left] += weight,
             }
         }
+        (w_b_terms, constant_terms)
+    }
 
-        let add_i_poly = MultilinearPolynomial::new(add_i_values.clone());
-        let mul_i_poly = MultilinearPolynomial::new(mul_i_values.clone());
-
-        (add_i_poly, mul_i_poly)
+    // Phase two, once b is fixed to u: f(u, c) = W(c) * w_c_terms(c) + constant_terms(c).
+    // `eq_u` is eq(u, b) over every b and `w_u` is W(u).
+    pub fn phase_two_tables(
+        &self,
+        layer_index: usize,
+        output_weights: &[F],
+        eq_u: &[F],
+        w_u: F,
+    ) -> (Vec<F>, Vec<F>) {
+        let size = 1 << self.layer_bits(layer_index + 1);
+        let mut w_c_terms = vec![F::zero(); size];
+        let mut constant_terms = vec![F::zero(); size];
+        for gate in self.layers[layer_index].gates.iter() {
+            let (_, value) = gate.op.predicate();
+            let weight = value * output_weights[gate.output] * eq_u[gate.left];
+            let right = gate.right;
+            match gate.op {
+                Op::Add => {
+                    w_c_terms[right] += weight;
+                    constant_terms[right] += weight * w_u;
+                }
+                Op::Mul => w_c_terms[right] += weight * w_u,
+                Op::Sub => {
+                    w_c_terms[right] -= weight;
+                    constant_terms[right] += weight * w_u;
+                }
+                Op::Relay | Op::ConstMul(_) => constant_terms[right] += weight * w_u,
+                Op::Const(_) => constant_terms[right] += weight,
+            }
+        }
+        (w_c_terms, constant_terms)
     }
+}
 
-    // New addi+1 = alpha * addi+1(rb, b, c) + beta * addi+1(rc, b, c)
-    // where alpha & beta are squeezed from transcript, rb = first half of random chal sent from the sumcheck prover and rc = second half of random chal sent from the sumcheck prover
-    pub fn alpha_beta_add_n_mul_bc(
+// The dense wiring predicates over (a, b, c) and the layer polynomial built from them, tables
+// of size 2^(2k) and up. Proving and verifying only use the sparse forms above; the tests keep
+// these as the reference to check them against.
+#[cfg(test)]
+impl<F: PrimeField> CircuitDescription<F> {
+    fn add_i_n_mul_i_arrays(
         &self,
-        alpha: F,
-        beta: F,
-        r_bs: &Vec<F>,
-        r_cs: &Vec<F>,
         layer_index: usize,
     ) -> (MultilinearPolynomial<F>, MultilinearPolynomial<F>) {
-        let (add_i_poly, mul_i_poly) = self.add_i_n_mul_i_arrays(layer_index);
-
-        let mut add_r_b = add_i_poly.partial_evaluate(0, r_bs[0]);
-        let mut add_r_c = add_i_poly.partial_evaluate(0, r_cs[0]);
+        let mut predicates = self.wiring_predicates(layer_index).into_iter();
+        (predicates.next().unwrap(), predicates.next().unwrap())
+    }
 
-        let mut mul_r_b = mul_i_poly.partial_evaluate(0, r_bs[0]);
-        let mut mul_r_c = mul_i_poly.partial_evaluate(0, r_cs[0]);
+    // add_i, mul_i, sub_i, relay_i, const_mul_i and const_i over (a, b, c); the last two hold
+    // the gate's constant instead of 1
+    fn wiring_predicates(&self, layer_index: usize) -> Vec<MultilinearPolynomial<F>> {
+        let output_bits = self.layer_bits(layer_index);
+        let input_bits = self.layer_bits(layer_index + 1);
+        let index_combinations = gate_index_combinations(output_bits, input_bits);
+        let boolean_combination = 1 << index_combinations;
 
-        for rb in r_bs.iter().skip(1) {
-            add_r_b = add_r_b.partial_evaluate(0, *rb);
-            mul_r_b = mul_r_b.partial_evaluate(0, *rb);
+        let mut values = vec![vec![F::zero(); boolean_combination]; NO_OF_PREDICATES];
+        for gate in self.layers[layer_index].gates.iter() {
+            let valid_index =
+                arrange_gate_index(output_bits, input_bits, gate.output, gate.left, gate.right);
+            let (predicate, value) = gate.op.predicate();
+            values[predicate][valid_index] = value;
         }
 
-        for rc in r_cs.iter().skip(1) {
-            add_r_c = add_r_c.partial_evaluate(0, *rc);
-            mul_r_c = mul_r_c.partial_evaluate(0, *rc);
-        }
+        values.into_iter().map(MultilinearPolynomial::new).collect()
+    }
+
+    // New addi+1 = alpha * addi+1(rb, b, c) + beta * addi+1(rc, b, c), and the same for every
+    // other predicate
+    // where alpha & beta are squeezed from transcript, rb = first half of random chal sent from the sumcheck prover and rc = second half of random chal sent from the sumcheck prover
+    fn alpha_beta_wiring_predicates(
+        &self,
+        alpha: F,
+        beta: F,
+        r_bs: &[F],
+        r_cs: &[F],
+        layer_index: usize,
+    ) -> Vec<MultilinearPolynomial<F>> {
+        self.predicates_over_inputs(layer_index, |output| {
+            alpha * eq_at(r_bs, output) + beta * eq_at(r_cs, output)
+        })
+    }
 
-        let new_add_i = add_polynomials(add_r_b.scalar_mul(alpha), add_r_c.scalar_mul(beta));
-        let new_mul_i = add_polynomials(mul_r_b.scalar_mul(alpha), mul_r_c.scalar_mul(beta));
+    // The wiring predicates with the output variables already summed out against
+    // `output_weight`, built straight from the gates. These are over (b, c) only, the size
+    // the sumcheck works on anyway.
+    fn predicates_over_inputs(
+        &self,
+        layer_index: usize,
+        output_weight: impl Fn(usize) -> F,
+    ) -> Vec<MultilinearPolynomial<F>> {
+        let input_bits = self.layer_bits(layer_index + 1);
+        let mut values = vec![vec![F::zero(); 1 << (2 * input_bits)]; NO_OF_PREDICATES];
+        for gate in self.layers[layer_index].gates.iter() {
+            let (predicate, value) = gate.op.predicate();
+            values[predicate][(gate.left << input_bits) | gate.right] +=
+                value * output_weight(gate.output);
+        }
 
-        (new_add_i, new_mul_i)
+        values.into_iter().map(MultilinearPolynomial::new).collect()
     }
 
-    pub fn f_b_c(
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn f_b_c(
         &self,
+        witness: &Witness<F>,
         layer_index: usize,
         a_s: Vec<F>,
         alpha: Option<F>,
@@ -178,19 +396,10 @@ impl<F: PrimeField> Circuit<F> {
         r_bs: Option<&Vec<F>>,
         r_cs: Option<&Vec<F>>,
     ) -> SumPoly<F> {
-        let (add_i_poly, mul_i_poly) = self.add_i_n_mul_i_arrays(layer_index);
-
-        let mut add_bc = add_i_poly;
-        let mut mul_bc = mul_i_poly;
-
-        let (add_bc, mul_bc) = if layer_index == 0 {
-            for i in 0..a_s.len() {
-                add_bc = add_bc.partial_evaluate(0, a_s[i]);
-                mul_bc = mul_bc.partial_evaluate(0, a_s[i]);
-            }
-            (add_bc, mul_bc)
+        let predicates = if layer_index == 0 {
+            self.predicates_over_inputs(layer_index, |output| eq_at(&a_s, output))
         } else {
-            self.alpha_beta_add_n_mul_bc(
+            self.alpha_beta_wiring_predicates(
                 alpha.unwrap(),
                 beta.unwrap(),
                 r_bs.unwrap(),
@@ -199,44 +408,106 @@ impl<F: PrimeField> Circuit<F> {
             )
         };
 
-        let w_i = self.w_i_polynomial(layer_index + 1);
-        let w_add_bc = tensor_add(w_i.clone(), w_i.clone());
-        let w_mul_bc = tensor_mul(w_i.clone(), w_i.clone());
-        println!("add_bc: {:?}", add_bc.clone());
-        println!("w_add_bc: {:?}", w_add_bc.clone());
-        SumPoly::new(vec![
-            ProductPoly::new(vec![add_bc, w_add_bc]),
-            ProductPoly::new(vec![mul_bc, w_mul_bc]),
-        ])
+        // what each kind of gate contributes at (b, c), in the order of the predicates
+        let w_i = witness.w_i_polynomial(layer_index + 1);
+        let ones = MultilinearPolynomial::new(vec![F::one(); w_i.coefficients.len()]);
+        let w_b = tensor_mul(w_i.clone(), ones.clone());
+        let contributions = [
+            tensor_add(w_i.clone(), w_i.clone()),
+            tensor_mul(w_i.clone(), w_i.clone()),
+            tensor_add(w_i.clone(), w_i.scalar_mul(-F::one())),
+            w_b.clone(),
+            w_b,
+            tensor_mul(ones.clone(), ones),
+        ];
+
+        SumPoly::new(
+            predicates
+                .into_iter()
+                .zip(contributions)
+                .map(|(predicate, contribution)| ProductPoly::new(vec![predicate, contribution]))
+                .collect(),
+        )
     }
 }
 
-fn gate_index_combinations(layer_index: usize) -> usize {
-    if layer_index == 0 {
-        return 3;
+// The layer polynomial at a point (b, c), given every wiring predicate and W_{i+1} there; the
+// same sum f_b_c builds as polynomials
+pub fn gate_contributions<F: PrimeField>(predicates: &[F], wb_eval: F, wc_eval: F) -> F {
+    predicates[0] * (wb_eval + wc_eval)
+        + predicates[1] * (wb_eval * wc_eval)
+        + predicates[2] * (wb_eval - wc_eval)
+        + predicates[3] * wb_eval
+        + predicates[4] * wb_eval
+        + predicates[5]
+}
+
+// eq(point, index) with the index's bits read most significant first, like the variables of
+// a MultilinearPolynomial
+fn eq_at<F: PrimeField>(point: &[F], index: usize) -> F {
+    let bits = point.len();
+    point
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            if (index >> (bits - 1 - i)) & 1 == 1 {
+                *r
+            } else {
+                F::one() - r
+            }
+        })
+        .product()
+}
+
+// eq(point, x) for every x on the hypercube, in O(2^n)
+pub(crate) fn eq_table<F: PrimeField>(point: &[F]) -> Vec<F> {
+    let mut table = vec![F::one()];
+    for r in point {
+        table = table
+            .iter()
+            .flat_map(|value| [*value * (F::one() - r), *value * r])
+            .collect();
     }
+    table
+}
 
-    let a = layer_index;
-    let b = layer_index + 1;
-    let c = layer_index + 1;
+fn width_bits(width: usize) -> usize {
+    (width.next_power_of_two().trailing_zeros() as usize).max(1)
+}
 
-    a + b + c
+// pads a layer with zero gates up to the next power of two
+pub(crate) fn padded_polynomial<F: PrimeField>(mut values: Vec<F>) -> MultilinearPolynomial<F> {
+    values.resize(1 << width_bits(values.len()), F::zero());
+    MultilinearPolynomial::new(values)
+}
+
+#[cfg(test)]
+// a wiring predicate has one variable per bit of the output gate and of both input gates
+fn gate_index_combinations(output_bits: usize, input_bits: usize) -> usize {
+    output_bits + 2 * input_bits
 }
 
+#[cfg(test)]
 fn arrange_gate_index(
-    layer_index: usize,
+    output_bits: usize,
+    input_bits: usize,
     output: usize,
     left_index: usize,
     right_index: usize,
 ) -> usize {
-    let output_binary = decimal_to_padded_binary(output, layer_index);
-    let left_binary = decimal_to_padded_binary(left_index, layer_index + 1);
-    let right_binary = decimal_to_padded_binary(right_index, layer_index + 1);
+    assert!(
+        output < 1 << output_bits && left_index < 1 << input_bits && right_index < 1 << input_bits,
+        "gate index out of range"
+    );
+    let output_binary = decimal_to_padded_binary(output, output_bits);
+    let left_binary = decimal_to_padded_binary(left_index, input_bits);
+    let right_binary = decimal_to_padded_binary(right_index, input_bits);
     let result = output_binary + &left_binary + &right_binary;
 
     usize::from_str_radix(&result, 2).unwrap_or(0)
 }
 
+#[cfg(test)]
 fn decimal_to_padded_binary(n: usize, bit_length: usize) -> String {
     format!("{:0>width$b}", n, width = bit_length)
 }
@@ -254,23 +525,23 @@ mod test {
     fn test_f_b_c() {
         let inputs = to_field(vec![1, 2, 3, 4, 5, 6, 7, 8]);
 
-        let gate_1: Gate = Gate::new('+', 0, 1, 0);
-        let gate_2: Gate = Gate::new('*', 2, 3, 1);
-        let gate_3: Gate = Gate::new('*', 4, 5, 2);
-        let gate_4: Gate = Gate::new('*', 6, 7, 3);
+        let gate_1 = Gate::new(Op::Add, 0, 1, 0);
+        let gate_2 = Gate::new(Op::Mul, 2, 3, 1);
+        let gate_3 = Gate::new(Op::Mul, 4, 5, 2);
+        let gate_4 = Gate::new(Op::Mul, 6, 7, 3);
 
-        let gate_5: Gate = Gate::new('+', 0, 1, 0);
-        let gate_6: Gate = Gate::new('*', 2, 3, 1);
+        let gate_5 = Gate::new(Op::Add, 0, 1, 0);
+        let gate_6 = Gate::new(Op::Mul, 2, 3, 1);
 
-        let gate_7: Gate = Gate::new('+', 0, 1, 0);
+        let gate_7 = Gate::new(Op::Add, 0, 1, 0);
 
         let layer_0 = Layer::init(vec![gate_7]);
         let layer_1 = Layer::init(vec![gate_5, gate_6]);
         let layer_2 = Layer::init(vec![gate_1, gate_2, gate_3, gate_4]);
 
-        let mut circuit = Circuit::create(inputs, vec![layer_0, layer_1, layer_2]);
-        circuit.execute();
-        let f_b_c = circuit.f_b_c(0, to_field(vec![5]), None, None, None, None);
+        let circuit = CircuitDescription::new(vec![layer_0, layer_1, layer_2], 8);
+        let witness = circuit.evaluate(&inputs);
+        let f_b_c = circuit.f_b_c(&witness, 0, to_field(vec![5]), None, None, None, None);
         dbg!(f_b_c);
     }
 
@@ -278,35 +549,34 @@ mod test {
     fn test_w_i_polynomial() {
         let inputs = to_field(vec![1, 2, 3, 4, 5, 6, 7, 8]);
 
-        let gate_1: Gate = Gate::new('+', 0, 1, 0);
-        let gate_2: Gate = Gate::new('*', 2, 3, 1);
-        let gate_3: Gate = Gate::new('*', 4, 5, 2);
-        let gate_4: Gate = Gate::new('*', 6, 7, 3);
+        let gate_1 = Gate::new(Op::Add, 0, 1, 0);
+        let gate_2 = Gate::new(Op::Mul, 2, 3, 1);
+        let gate_3 = Gate::new(Op::Mul, 4, 5, 2);
+        let gate_4 = Gate::new(Op::Mul, 6, 7, 3);
 
-        let gate_5: Gate = Gate::new('+', 0, 1, 0);
-        let gate_6: Gate = Gate::new('*', 2, 3, 1);
+        let gate_5 = Gate::new(Op::Add, 0, 1, 0);
+        let gate_6 = Gate::new(Op::Mul, 2, 3, 1);
 
-        let gate_7: Gate = Gate::new('+', 0, 1, 0);
+        let gate_7 = Gate::new(Op::Add, 0, 1, 0);
 
         let layer_0 = Layer::init(vec![gate_7]);
         let layer_1 = Layer::init(vec![gate_5, gate_6]);
         let layer_2 = Layer::init(vec![gate_1, gate_2, gate_3, gate_4]);
 
-        let mut circuit = Circuit::create(inputs, vec![layer_0, layer_1, layer_2]);
-        circuit.execute();
-        let w_i = circuit.w_i_polynomial(1);
+        let circuit = CircuitDescription::new(vec![layer_0, layer_1, layer_2], 8);
+        let w_i = circuit.evaluate(&inputs).w_i_polynomial(1);
         assert_eq!(w_i.coefficients, to_field(vec![15, 1680]));
     }
 
     #[test]
     fn test_gate_index_combinations() {
-        let index = gate_index_combinations(1);
+        let index = gate_index_combinations(1, 2);
         assert_eq!(index, 5);
     }
 
     #[test]
     fn test_arrange_gate_index() {
-        let index = arrange_gate_index(1, 1, 2, 3);
+        let index = arrange_gate_index(1, 2, 1, 2, 3);
         let expected = usize::from_str_radix("11011", 2).unwrap_or(0);
         assert_eq!(index, expected);
     }
@@ -319,22 +589,21 @@ mod test {
 
     #[test]
     fn test_add_i_n_mul_i_arrays() {
-        let inputs = to_field(vec![1, 2, 3, 4, 5, 6, 7, 8]);
-        let gate_1: Gate = Gate::new('+', 0, 1, 0);
-        let gate_2: Gate = Gate::new('*', 2, 3, 1);
-        let gate_3: Gate = Gate::new('*', 4, 5, 2);
-        let gate_4: Gate = Gate::new('*', 6, 7, 3);
+        let gate_1 = Gate::new(Op::Add, 0, 1, 0);
+        let gate_2 = Gate::new(Op::Mul, 2, 3, 1);
+        let gate_3 = Gate::new(Op::Mul, 4, 5, 2);
+        let gate_4 = Gate::new(Op::Mul, 6, 7, 3);
 
-        let gate_5: Gate = Gate::new('+', 0, 1, 0);
-        let gate_6: Gate = Gate::new('*', 2, 3, 1);
+        let gate_5 = Gate::new(Op::Add, 0, 1, 0);
+        let gate_6 = Gate::new(Op::Mul, 2, 3, 1);
 
-        let gate_7: Gate = Gate::new('+', 0, 1, 0);
+        let gate_7 = Gate::new(Op::Add, 0, 1, 0);
 
         let layer_0 = Layer::init(vec![gate_7]);
         let layer_1 = Layer::init(vec![gate_5, gate_6]);
         let layer_2 = Layer::init(vec![gate_1, gate_2, gate_3, gate_4]);
 
-        let circuit = Circuit::create(inputs, vec![layer_0, layer_1, layer_2]);
+        let circuit = CircuitDescription::<Fq>::new(vec![layer_0, layer_1, layer_2], 8);
         let (add_i_poly, mul_i_poly) = circuit.add_i_n_mul_i_arrays(1);
         assert_eq!(
             add_i_poly.coefficients,
@@ -357,25 +626,99 @@ mod test {
         // assert_eq!(mul_i_values, vec!["01010011", "10100101", "11110111"]);
     }
 
+    #[test]
+    fn test_sparse_predicates_match_dense() {
+        // three gates over five inputs, two of them reading the same pair of wires
+        let layer_0 = Layer::init(vec![
+            Gate::new(Op::Add, 0, 1, 0),
+            Gate::new(Op::ConstMul(Fq::from(4)), 2, 2, 1),
+            Gate::new(Op::Const(Fq::from(9)), 0, 0, 2),
+        ]);
+        let layer_1 = Layer::init(vec![
+            Gate::new(Op::Mul, 0, 1, 0),
+            Gate::new(Op::Sub, 0, 1, 1),
+            Gate::new(Op::Relay, 4, 4, 2),
+        ]);
+        let circuit = CircuitDescription::<Fq>::new(vec![layer_0, layer_1], 5);
+
+        let r_a = to_field(vec![3, 5]);
+        let r_b = to_field(vec![7, 11, 13]);
+        let r_c = to_field(vec![17, 19, 23]);
+        let point: Vec<Fq> = r_a.iter().chain(&r_b).chain(&r_c).copied().collect();
+        let dense: Vec<Fq> = circuit
+            .wiring_predicates(1)
+            .iter()
+            .map(|predicate| predicate.evaluate(&point))
+            .collect();
+        assert_eq!(
+            circuit.evaluate_wiring_predicates(1, &r_a, &r_b, &r_c),
+            dense
+        );
+
+        // the same with the output variables folded in first, as the prover does
+        let (alpha, beta) = (Fq::from(2), Fq::from(29));
+        let r_bs = to_field(vec![31, 37]);
+        let folded = circuit.alpha_beta_wiring_predicates(alpha, beta, &r_bs, &r_a, 1);
+        let at_b_c: Vec<Fq> = r_b.iter().chain(&r_c).copied().collect();
+        let expected: Vec<Fq> = circuit
+            .evaluate_wiring_predicates(1, &r_bs, &r_b, &r_c)
+            .into_iter()
+            .zip(dense)
+            .map(|(at_rb, at_rc)| alpha * at_rb + beta * at_rc)
+            .collect();
+        let received: Vec<Fq> = folded.iter().map(|p| p.evaluate(&at_b_c)).collect();
+        assert_eq!(received, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "gate 1 of layer 1 reads wires 2 and 4, but the layer below has 4")]
+    fn test_circuit_rejects_missing_wires() {
+        let layer_0 = Layer::init(vec![Gate::new(Op::Add, 0, 1, 0)]);
+        let layer_1 = Layer::init(vec![
+            Gate::new(Op::Add, 0, 1, 0),
+            Gate::new(Op::Mul, 2, 4, 1),
+        ]);
+        CircuitDescription::<Fq>::new(vec![layer_0, layer_1], 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "gate 1 of layer 0 claims to output wire 0")]
+    fn test_circuit_rejects_misplaced_outputs() {
+        let layer_0 = Layer::init(vec![
+            Gate::new(Op::Add, 0, 1, 0),
+            Gate::new(Op::Mul, 2, 3, 0),
+        ]);
+        CircuitDescription::<Fq>::new(vec![layer_0], 4);
+    }
+
     #[test]
     fn test_gate_operate() {
         let inputs = to_field(vec![1, 2]);
-        let mut add_gate = Gate::new('+', 0, 1, 0);
-        let mut mul_gate = Gate::new('*', 0, 1, 1);
+        let add_gate = Gate::new(Op::Add, 0, 1, 0);
+        let mul_gate = Gate::new(Op::Mul, 0, 1, 1);
 
-        let add_output = add_gate.operate(inputs.clone());
-        let mul_output = mul_gate.operate(inputs);
+        let add_output = add_gate.operate(&inputs);
+        let mul_output = mul_gate.operate(&inputs);
         assert_eq!(add_output, Fq::from(3));
         assert_eq!(mul_output, Fq::from(2));
+
+        let sub_gate = Gate::new(Op::Sub, 0, 1, 2);
+        let relay_gate = Gate::new(Op::Relay, 1, 1, 3);
+        let const_mul_gate = Gate::new(Op::ConstMul(Fq::from(5)), 1, 1, 4);
+        let const_gate = Gate::new(Op::Const(Fq::from(9)), 0, 0, 5);
+        assert_eq!(sub_gate.operate(&inputs), -Fq::from(1));
+        assert_eq!(relay_gate.operate(&inputs), Fq::from(2));
+        assert_eq!(const_mul_gate.operate(&inputs), Fq::from(10));
+        assert_eq!(const_gate.operate(&inputs), Fq::from(9));
     }
 
     #[test]
     fn test_layer_compute() {
         let inputs = to_field(vec![1, 2, 3, 4]);
-        let add_gate = Gate::new('+', 0, 1, 0);
-        let mul_gate = Gate::new('*', 2, 3, 0);
-        let mut layer = Layer::init(vec![add_gate, mul_gate]);
-        let outputs = layer.compute(inputs);
+        let add_gate = Gate::new(Op::Add, 0, 1, 0);
+        let mul_gate = Gate::new(Op::Mul, 2, 3, 0);
+        let layer = Layer::init(vec![add_gate, mul_gate]);
+        let outputs = layer.compute(&inputs);
         assert_eq!(outputs, to_field(vec![3, 12]));
     }
 
@@ -383,44 +726,51 @@ mod test {
     fn test_circuit_execute() {
         let inputs = to_field(vec![1, 2, 3, 4]);
 
-        let gate_1: Gate = Gate::new('+', 0, 1, 0);
-        let gate_2: Gate = Gate::new('*', 2, 3, 1);
+        let gate_1 = Gate::new(Op::Add, 0, 1, 0);
+        let gate_2 = Gate::new(Op::Mul, 2, 3, 1);
 
-        let gate_3: Gate = Gate::new('+', 0, 1, 0);
+        let gate_3 = Gate::new(Op::Add, 0, 1, 0);
 
         let layer_0 = Layer::init(vec![gate_3]);
         let layer_1 = Layer::init(vec![gate_1, gate_2]);
 
-        let mut circuit = Circuit::create(inputs, vec![layer_0, layer_1]);
-        let circuit_eval = circuit.execute();
+        let circuit = CircuitDescription::new(vec![layer_0, layer_1], 4);
+        let witness = circuit.evaluate(&inputs);
 
         assert_eq!(
-            circuit_eval,
+            witness.layers,
             vec![to_field(vec![15]), to_field(vec![3, 12])]
         );
-        dbg!(circuit);
+        assert_eq!(witness.outputs(), to_field(vec![15]));
+
+        // the description is left untouched, so evaluating again gives the same witness
+        assert_eq!(circuit.evaluate(&inputs), witness);
+        assert_eq!(
+            circuit.evaluate(&to_field(vec![2, 2, 2, 2])).layers,
+            vec![to_field(vec![8]), to_field(vec![4, 4])]
+        );
     }
 
     #[test]
     fn test_circuit_execute_2() {
         let inputs = to_field(vec![1, 2, 3, 4, 5, 6, 7, 8]);
 
-        let gate_1: Gate = Gate::new('+', 0, 1, 0);
-        let gate_2: Gate = Gate::new('*', 2, 3, 1);
-        let gate_3: Gate = Gate::new('*', 4, 5, 2);
-        let gate_4: Gate = Gate::new('*', 6, 7, 3);
+        let gate_1 = Gate::new(Op::Add, 0, 1, 0);
+        let gate_2 = Gate::new(Op::Mul, 2, 3, 1);
+        let gate_3 = Gate::new(Op::Mul, 4, 5, 2);
+        let gate_4 = Gate::new(Op::Mul, 6, 7, 3);
 
-        let gate_5: Gate = Gate::new('+', 0, 1, 0);
-        let gate_6: Gate = Gate::new('*', 2, 3, 1);
+        let gate_5 = Gate::new(Op::Add, 0, 1, 0);
+        let gate_6 = Gate::new(Op::Mul, 2, 3, 1);
 
-        let gate_7: Gate = Gate::new('+', 0, 1, 0);
+        let gate_7 = Gate::new(Op::Add, 0, 1, 0);
 
         let layer_0 = Layer::init(vec![gate_7]);
         let layer_1 = Layer::init(vec![gate_5, gate_6]);
         let layer_2 = Layer::init(vec![gate_1, gate_2, gate_3, gate_4]);
 
-        let mut circuit = Circuit::create(inputs, vec![layer_0, layer_1, layer_2]);
-        let circuit_eval = circuit.execute();
+        let circuit = CircuitDescription::new(vec![layer_0, layer_1, layer_2], 8);
+        let circuit_eval = circuit.evaluate(&inputs).layers;
 
         assert_eq!(
             circuit_eval,
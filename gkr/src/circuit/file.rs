@@ -0,0 +1,353 @@
+use super::{CircuitDescription, Gate, Layer, Op};
+use ark_ff::PrimeField;
+use std::{fmt, fs, io, path::Path};
+
+// A plain text circuit description, one statement per line, for circuits generated outside
+// of Rust:
+//
+//     # (x0 + x1) * (x2 - 3)
+//     inputs 3
+//     constants 3
+//     layer 1
+//     mul 0 1
+//     layer 2
+//     add 0 1
+//     sub 2 3
+//
+// `inputs` is the number of public input wires and the optional `constants` line lists the
+// constant inputs that follow them on the input layer. Layers come output first like
+// CircuitDescription::layers, so the gates of the first layer are the outputs. `layer <width>`
+// is followed by exactly `width` gates, in output order:
+//
+//     add a b | mul a b | sub a b | relay a | cmul k a | const k
+//
+// where a and b index the layer below and k is a field element in decimal, possibly negative.
+// Everything after a # is a comment.
+impl<F: PrimeField> CircuitDescription<F> {
+    pub fn parse(input: &str) -> Result<Self, ParseCircuitError> {
+        let mut no_of_inputs = None;
+        let mut constant_inputs = Vec::new();
+        let mut layers: Vec<ParsedLayer<F>> = Vec::new();
+
+        for (index, line) in input.lines().enumerate() {
+            let line_number = index + 1;
+            let error = |reason: String| ParseCircuitError {
+                line: line_number,
+                reason,
+            };
+
+            let statement = line.split('#').next().unwrap();
+            let fields: Vec<&str> = statement.split_whitespace().collect();
+            let Some((keyword, arguments)) = fields.split_first() else {
+                continue;
+            };
+            let expect_arguments = |count: usize| {
+                if arguments.len() == count {
+                    Ok(())
+                } else {
+                    Err(error(format!(
+                        "{keyword} takes {count} arguments, found {}",
+                        arguments.len()
+                    )))
+                }
+            };
+            let wire = |field: &str| {
+                field
+                    .parse::<usize>()
+                    .map_err(|_| error(format!("invalid wire index {field}")))
+            };
+            let value = |field: &str| {
+                F::from_str(field).map_err(|_| error(format!("invalid field element {field}")))
+            };
+
+            match *keyword {
+                "inputs" => {
+                    expect_arguments(1)?;
+                    if no_of_inputs.is_some() || !layers.is_empty() {
+                        return Err(error("inputs has to come once, before the layers".into()));
+                    }
+                    no_of_inputs = Some(
+                        arguments[0]
+                            .parse::<usize>()
+                            .map_err(|_| error(format!("invalid input count {}", arguments[0])))?,
+                    );
+                }
+                "constants" => {
+                    if !layers.is_empty() {
+                        return Err(error("constants have to come before the layers".into()));
+                    }
+                    for argument in arguments {
+                        constant_inputs.push(value(argument)?);
+                    }
+                }
+                "layer" => {
+                    expect_arguments(1)?;
+                    if no_of_inputs.is_none() {
+                        return Err(error("layer before inputs".into()));
+                    }
+                    if let Some(layer) = layers.last() {
+                        layer.check_width()?;
+                    }
+                    let width = arguments[0]
+                        .parse::<usize>()
+                        .map_err(|_| error(format!("invalid layer width {}", arguments[0])))?;
+                    if width == 0 {
+                        return Err(error("layer has no gates".into()));
+                    }
+                    layers.push(ParsedLayer {
+                        line: line_number,
+                        width,
+                        gates: Vec::new(),
+                    });
+                }
+                op => {
+                    let (op, left, right) = match op {
+                        "add" | "mul" | "sub" => {
+                            expect_arguments(2)?;
+                            let op = match op {
+                                "add" => Op::Add,
+                                "mul" => Op::Mul,
+                                _ => Op::Sub,
+                            };
+                            (op, wire(arguments[0])?, wire(arguments[1])?)
+                        }
+                        "relay" => {
+                            expect_arguments(1)?;
+                            let left = wire(arguments[0])?;
+                            (Op::Relay, left, left)
+                        }
+                        "cmul" => {
+                            expect_arguments(2)?;
+                            let left = wire(arguments[1])?;
+                            (Op::ConstMul(value(arguments[0])?), left, left)
+                        }
+                        "const" => {
+                            expect_arguments(1)?;
+                            (Op::Const(value(arguments[0])?), 0, 0)
+                        }
+                        _ => return Err(error(format!("unknown statement {op}"))),
+                    };
+
+                    let Some(layer) = layers.last_mut() else {
+                        return Err(error("gate outside of a layer".into()));
+                    };
+                    if layer.gates.len() == layer.width {
+                        return Err(error(format!("layer already has {} gates", layer.width)));
+                    }
+                    let output = layer.gates.len();
+                    layer
+                        .gates
+                        .push((line_number, Gate::new(op, left, right, output)));
+                }
+            }
+        }
+
+        let Some(no_of_inputs) = no_of_inputs else {
+            return Err(ParseCircuitError {
+                line: input.lines().count(),
+                reason: "missing inputs".into(),
+            });
+        };
+        match layers.last() {
+            Some(layer) => layer.check_width()?,
+            None => {
+                return Err(ParseCircuitError {
+                    line: input.lines().count(),
+                    reason: "circuit has no layers".into(),
+                })
+            }
+        }
+
+        // every wire a gate reads has to exist on the layer below it
+        let input_width = no_of_inputs + constant_inputs.len();
+        for (layer_index, layer) in layers.iter().enumerate() {
+            let below = match layers.get(layer_index + 1) {
+                Some(layer) => layer.width,
+                None => input_width,
+            };
+            for (line, gate) in &layer.gates {
+                if let Some(wire) = [gate.left, gate.right].into_iter().find(|w| *w >= below) {
+                    return Err(ParseCircuitError {
+                        line: *line,
+                        reason: format!("wire {wire} is out of range, the layer below has {below}"),
+                    });
+                }
+            }
+        }
+
+        let layers = layers
+            .into_iter()
+            .map(|layer| Layer::init(layer.gates.into_iter().map(|(_, gate)| gate).collect()))
+            .collect();
+        Ok(Self::with_constant_inputs(
+            layers,
+            no_of_inputs,
+            constant_inputs,
+        ))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadCircuitError> {
+        let input = fs::read_to_string(path).map_err(LoadCircuitError::Io)?;
+        Self::parse(&input).map_err(LoadCircuitError::Parse)
+    }
+}
+
+impl<F: PrimeField> fmt::Display for CircuitDescription<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "inputs {}", self.no_of_inputs)?;
+        if !self.constant_inputs.is_empty() {
+            write!(f, "constants")?;
+            for constant in &self.constant_inputs {
+                write!(f, " {constant}")?;
+            }
+            writeln!(f)?;
+        }
+        for layer in &self.layers {
+            writeln!(f, "layer {}", layer.gates.len())?;
+            for gate in &layer.gates {
+                match gate.op {
+                    Op::Add => writeln!(f, "add {} {}", gate.left, gate.right)?,
+                    Op::Mul => writeln!(f, "mul {} {}", gate.left, gate.right)?,
+                    Op::Sub => writeln!(f, "sub {} {}", gate.left, gate.right)?,
+                    Op::Relay => writeln!(f, "relay {}", gate.left)?,
+                    Op::ConstMul(constant) => writeln!(f, "cmul {constant} {}", gate.left)?,
+                    Op::Const(constant) => writeln!(f, "const {constant}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// a layer as it is being read, with the line of its header and of every gate
+struct ParsedLayer<F: PrimeField> {
+    line: usize,
+    width: usize,
+    gates: Vec<(usize, Gate<F>)>,
+}
+
+impl<F: PrimeField> ParsedLayer<F> {
+    fn check_width(&self) -> Result<(), ParseCircuitError> {
+        if self.gates.len() == self.width {
+            return Ok(());
+        }
+        Err(ParseCircuitError {
+            line: self.line,
+            reason: format!(
+                "layer declares {} gates but has {}",
+                self.width,
+                self.gates.len()
+            ),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCircuitError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseCircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for ParseCircuitError {}
+
+#[derive(Debug)]
+pub enum LoadCircuitError {
+    Io(io::Error),
+    Parse(ParseCircuitError),
+}
+
+impl fmt::Display for LoadCircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadCircuitError::Io(error) => write!(f, "{error}"),
+            LoadCircuitError::Parse(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadCircuitError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::CircuitBuilder;
+    use crate::gkr::{prove, verify};
+    use ark_bn254::Fq;
+
+    fn to_field(input: Vec<u64>) -> Vec<Fq> {
+        input.into_iter().map(Fq::from).collect()
+    }
+
+    fn parse_error(input: &str) -> ParseCircuitError {
+        CircuitDescription::<Fq>::parse(input).unwrap_err()
+    }
+
+    #[test]
+    fn test_parse_and_prove() {
+        let input = "\
+            # (x0 + x1) * (x2 - 3)
+            inputs 3
+            constants 3
+            layer 1
+            mul 0 1   # the output
+            layer 2
+            add 0 1
+            sub 2 3
+        ";
+        let circuit = CircuitDescription::<Fq>::parse(input).unwrap();
+        let inputs = to_field(vec![1, 2, 10]);
+        assert_eq!(circuit.evaluate(&inputs).outputs(), to_field(vec![21]));
+
+        let proof = prove(&circuit, &inputs);
+        assert!(verify(&circuit, &inputs, &proof).is_ok());
+    }
+
+    #[test]
+    fn test_write_and_parse_round_trip() {
+        // every op, a negative constant and a constant input
+        let mut builder = CircuitBuilder::new();
+        let x = [builder.input(), builder.input()];
+        let difference = builder.sub(x[0], x[1]);
+        let scaled = builder.mul_constant(difference, -Fq::from(5));
+        let product = builder.mul(x[0], x[1]);
+        let seven = builder.constant(Fq::from(7));
+        let shifted = builder.add(product, seven);
+        let sum = builder.add(scaled, shifted);
+        builder.mark_output(sum);
+        builder.mark_output(x[0]);
+        builder.mark_output(seven);
+        let circuit = builder.build();
+
+        let written = circuit.to_string();
+        let parsed = CircuitDescription::<Fq>::parse(&written).unwrap();
+        assert_eq!(parsed.to_string(), written);
+
+        let inputs = to_field(vec![9, 4]);
+        assert_eq!(parsed.evaluate(&inputs), circuit.evaluate(&inputs));
+        let proof = prove(&parsed, &inputs);
+        assert!(verify(&circuit, &inputs, &proof).is_ok());
+    }
+
+    #[test]
+    fn test_parse_reports_line() {
+        let error = parse_error("inputs 2\nlayer 1\nadd 0 1\nlayer 2\nmul 0 1\nrelay 2\n");
+        assert_eq!(error.line, 6);
+        assert_eq!(
+            error.to_string(),
+            "line 6: wire 2 is out of range, the layer below has 2"
+        );
+
+        assert_eq!(parse_error("inputs 2\nlayer 1\nadd 0 7\n").line, 3);
+        assert_eq!(parse_error("inputs 2\nlayer 2\nadd 0 1\n").line, 2);
+        assert_eq!(parse_error("inputs 2\nlayer 1\nadd 0\n").line, 3);
+        assert_eq!(parse_error("inputs 2\nlayer 1\nxor 0 1\n").line, 3);
+        assert_eq!(parse_error("inputs 2\n\nlayer 1\ncmul x 0\n").line, 4);
+        assert_eq!(parse_error("layer 1\nadd 0 1\n").line, 1);
+    }
+}
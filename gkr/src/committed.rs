@@ -0,0 +1,271 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use kzg::{commit, error::KZGError, open, trusted_setup::TrustedSetup, verify_opening, KZGProof};
+use multivariate_poly::MultilinearPolynomial;
+use sha3::Keccak256;
+use sum_check::{
+    error::VerificationError,
+    transcript::{HashTrait, Transcript},
+};
+
+use crate::{
+    circuit::CircuitDescription,
+    gkr::{prove_layers, verify_layers, Proof},
+};
+
+// GKR as an argument: the verifier gets a multilinear KZG commitment to the inputs instead of
+// the inputs themselves, and the two claims on the input layer left by the last sumcheck are
+// opened against it. Only the public inputs are committed: both sides run the circuit with its
+// constant inputs lowered to Const gates, so no committed slot can stand in for a constant. The
+// setup needs one tau per variable of the public inputs.
+#[derive(Debug, Clone)]
+pub struct CommittedProof<F: PrimeField, P: Pairing> {
+    pub proof: Proof<F>,
+    // the committed inputs opened at the last layer's rb and rc
+    pub wb_opening: KZGProof<F, P>,
+    pub wc_opening: KZGProof<F, P>,
+}
+
+pub fn commit_inputs<F: PrimeField, P: Pairing>(
+    circuit: &CircuitDescription<F>,
+    inputs: &[F],
+    setup: &TrustedSetup<P>,
+) -> P::G1 {
+    let input_polynomial = committed_input_polynomial(circuit, inputs, setup);
+    commit::<F, P>(&setup.g1_taus, &input_polynomial.coefficients)
+}
+
+pub fn prove_committed<F: PrimeField, P: Pairing>(
+    circuit: &CircuitDescription<F>,
+    inputs: &[F],
+    setup: &TrustedSetup<P>,
+) -> CommittedProof<F, P> {
+    prove_committed_with_hasher(circuit, inputs, setup, Keccak256::default())
+}
+
+pub fn prove_committed_with_hasher<F: PrimeField, P: Pairing, K: HashTrait>(
+    circuit: &CircuitDescription<F>,
+    inputs: &[F],
+    setup: &TrustedSetup<P>,
+    hash_function: K,
+) -> CommittedProof<F, P> {
+    let circuit = circuit.with_constants_as_gates();
+    let input_polynomial = committed_input_polynomial(&circuit, inputs, setup);
+    let commitment = commit::<F, P>(&setup.g1_taus, &input_polynomial.coefficients);
+
+    let witness = circuit.evaluate(inputs);
+    let mut transcript: Transcript<K, F> = Transcript::init(hash_function, b"gkr");
+    append_commitment::<F, P, K>(&mut transcript, &commitment);
+    let proof = prove_layers(&circuit, &witness, &mut transcript);
+
+    let random_challenges = &proof.sumcheck_proofs.last().unwrap().random_challenges;
+    let (rb_values, rc_values) = random_challenges.split_at(random_challenges.len() / 2);
+    CommittedProof {
+        wb_opening: open(setup, &input_polynomial, rb_values),
+        wc_opening: open(setup, &input_polynomial, rc_values),
+        proof,
+    }
+}
+
+// Checks that the inputs behind `commitment` make the circuit output `proof.proof.outputs`
+pub fn verify_committed<F: PrimeField, P: Pairing>(
+    circuit: &CircuitDescription<F>,
+    commitment: &P::G1,
+    proof: &CommittedProof<F, P>,
+    setup: &TrustedSetup<P>,
+) -> Result<(), VerificationError<F>> {
+    verify_committed_with_hasher(circuit, commitment, proof, setup, Keccak256::default())
+}
+
+pub fn verify_committed_with_hasher<F: PrimeField, P: Pairing, K: HashTrait>(
+    circuit: &CircuitDescription<F>,
+    commitment: &P::G1,
+    proof: &CommittedProof<F, P>,
+    setup: &TrustedSetup<P>,
+    hash_function: K,
+) -> Result<(), VerificationError<F>> {
+    let mut transcript: Transcript<K, F> = Transcript::init(hash_function, b"gkr");
+    append_commitment::<F, P, K>(&mut transcript, commitment);
+
+    verify_layers(
+        &circuit.with_constants_as_gates(),
+        &proof.proof,
+        &mut transcript,
+        |random_challenges| {
+            let (rb_values, rc_values) = random_challenges.split_at(random_challenges.len() / 2);
+            let mut evals = [F::zero(); 2];
+            for (eval, (opening, point)) in evals.iter_mut().zip([
+                (&proof.wb_opening, rb_values),
+                (&proof.wc_opening, rc_values),
+            ]) {
+                if opening.commitment != *commitment {
+                    return Err(VerificationError::CommitmentMismatch);
+                }
+                verify_opening(setup, opening, point).map_err(opening_error)?;
+                *eval = opening.poly_opened;
+            }
+            Ok((evals[0], evals[1]))
+        },
+    )
+}
+
+fn committed_input_polynomial<F: PrimeField, P: Pairing>(
+    circuit: &CircuitDescription<F>,
+    inputs: &[F],
+    setup: &TrustedSetup<P>,
+) -> MultilinearPolynomial<F> {
+    let input_polynomial = circuit.with_constants_as_gates().input_polynomial(inputs);
+    assert_eq!(
+        setup.g2_taus.len(),
+        input_polynomial.no_of_variables(),
+        "the setup needs one tau per variable of the public inputs"
+    );
+    input_polynomial
+}
+
+// kzg has its own error type so it doesn't depend on sumcheck; the proof errors carry over
+fn opening_error<F: PrimeField>(error: KZGError) -> VerificationError<F> {
+    match error {
+        KZGError::MalformedProof {
+            field,
+            expected,
+            received,
+        } => VerificationError::MalformedProof {
+            field,
+            expected,
+            received,
+        },
+        KZGError::PairingCheckFailed => VerificationError::PairingCheckFailed,
+    }
+}
+
+// the commitment goes in before anything else, so every challenge depends on it
+fn append_commitment<F: PrimeField, P: Pairing, K: HashTrait>(
+    transcript: &mut Transcript<K, F>,
+    commitment: &P::G1,
+) {
+    let mut bytes = Vec::new();
+    commitment
+        .serialize_compressed(&mut bytes)
+        .expect("serializing to a Vec can't fail");
+    transcript.append_message(b"input_commitment", &bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder::CircuitBuilder, circuit::padded_polynomial};
+    use ark_bls12_381::{Bls12_381, Fr};
+
+    fn to_field(input: Vec<u64>) -> Vec<Fr> {
+        input.into_iter().map(Fr::from).collect()
+    }
+
+    // (x0 + x1) * (x2 - 3) and x3 * 5; four public inputs, so two variables to commit
+    fn circuit() -> CircuitDescription<Fr> {
+        let mut builder = CircuitBuilder::new();
+        let x: Vec<_> = (0..4).map(|_| builder.input()).collect();
+        let sum = builder.add(x[0], x[1]);
+        let three = builder.constant(Fr::from(3));
+        let difference = builder.sub(x[2], three);
+        let output = builder.mul(sum, difference);
+        let scaled = builder.mul_constant(x[3], Fr::from(5));
+        builder.mark_output(output);
+        builder.mark_output(scaled);
+        builder.build()
+    }
+
+    fn setup() -> TrustedSetup<Bls12_381> {
+        TrustedSetup::initialize(&to_field(vec![5, 2]))
+    }
+
+    #[test]
+    fn test_committed_inputs() {
+        let circuit = circuit();
+        let setup = setup();
+        let inputs = to_field(vec![1, 2, 10, 4]);
+
+        let commitment = commit_inputs(&circuit, &inputs, &setup);
+        let proof = prove_committed(&circuit, &inputs, &setup);
+        assert_eq!(proof.proof.outputs, to_field(vec![21, 20]));
+        assert!(verify_committed(&circuit, &commitment, &proof, &setup).is_ok());
+
+        let mut tampered = proof.clone();
+        tampered.wc_opening.poly_opened += Fr::from(1);
+        assert_eq!(
+            verify_committed(&circuit, &commitment, &tampered, &setup),
+            Err(VerificationError::PairingCheckFailed.at_layer(2))
+        );
+
+        let mut tampered = proof.clone();
+        tampered.proof.outputs[1] += Fr::from(1);
+        assert!(verify_committed(&circuit, &commitment, &tampered, &setup).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "the setup needs one tau per variable of the public inputs")]
+    fn test_commit_inputs_checks_setup_size() {
+        let setup = TrustedSetup::<Bls12_381>::initialize(&to_field(vec![5, 2, 3]));
+        let _ = commit_inputs(&circuit(), &to_field(vec![1, 2, 10, 4]), &setup);
+    }
+
+    #[test]
+    fn test_committed_inputs_reject_other_commitment() {
+        let circuit = circuit();
+        let setup = setup();
+        let inputs = to_field(vec![1, 2, 10, 4]);
+        let proof = prove_committed(&circuit, &inputs, &setup);
+
+        // the transcript starts from the commitment, so the layers fail before any opening
+        let other = commit_inputs(&circuit, &to_field(vec![1, 2, 10, 5]), &setup);
+        assert!(verify_committed(&circuit, &other, &proof, &setup).is_err());
+
+        // an opening for some other commitment is caught before its pairing check
+        let mut tampered = proof;
+        tampered.wb_opening.commitment = other;
+        let commitment = commit_inputs(&circuit, &inputs, &setup);
+        assert_eq!(
+            verify_committed(&circuit, &commitment, &tampered, &setup),
+            Err(VerificationError::CommitmentMismatch.at_layer(2))
+        );
+    }
+
+    #[test]
+    fn test_committed_inputs_reject_a_committed_constant() {
+        // (x0 + x1) * (x2 - c): three public inputs, so the fourth committed slot is padding,
+        // right where the constant sits in the input layer
+        let circuit = |constant: u64| {
+            let mut builder = CircuitBuilder::new();
+            let x: Vec<_> = (0..3).map(|_| builder.input()).collect();
+            let sum = builder.add(x[0], x[1]);
+            let constant = builder.constant(Fr::from(constant));
+            let difference = builder.sub(x[2], constant);
+            let output = builder.mul(sum, difference);
+            builder.mark_output(output);
+            builder.build()
+        };
+        let honest = circuit(3);
+        let setup = setup();
+        let inputs = to_field(vec![1, 2, 10]);
+
+        // commit a 1 in that slot and prove as if the constant were 3 + 1
+        let input_polynomial = padded_polynomial(to_field(vec![1, 2, 10, 1]));
+        let commitment = commit::<Fr, Bls12_381>(&setup.g1_taus, &input_polynomial.coefficients);
+        let witness = circuit(4).with_constants_as_gates().evaluate(&inputs);
+        let mut transcript: Transcript<Keccak256, Fr> =
+            Transcript::init(Keccak256::default(), b"gkr");
+        append_commitment::<Fr, Bls12_381, Keccak256>(&mut transcript, &commitment);
+        let proof = prove_layers(&honest.with_constants_as_gates(), &witness, &mut transcript);
+        let random_challenges = &proof.sumcheck_proofs.last().unwrap().random_challenges;
+        let (rb_values, rc_values) = random_challenges.split_at(random_challenges.len() / 2);
+        let proof = CommittedProof {
+            wb_opening: open(&setup, &input_polynomial, rb_values),
+            wc_opening: open(&setup, &input_polynomial, rc_values),
+            proof,
+        };
+
+        assert_eq!(proof.proof.outputs, to_field(vec![18]));
+        assert!(verify_committed(&honest, &commitment, &proof, &setup).is_err());
+    }
+}
@@ -0,0 +1,371 @@
+use ark_ff::PrimeField;
+use multivariate_poly::MultilinearPolynomial;
+use sha3::Keccak256;
+use sum_check::{
+    error::VerificationError,
+    transcript::{HashTrait, Transcript},
+};
+
+use crate::{
+    circuit::{eq_table, padded_polynomial, CircuitDescription},
+    gkr::{compute_folded_claim, compute_initial_claim},
+    sumcheck::{partial_prove_data_parallel, partial_verify_data_parallel, PartialProof},
+};
+
+// GKR for one sub-circuit applied to many independent inputs. Every layer of the big circuit is
+// the sub-circuit's layer once per copy, so W_i(z, a) gets s copy variables z in front of the
+// gate variables and
+//
+//     W_i(r_z, r_a) = sum_{z, b, c} eq(r_z, z) * f_i(r_a, b, c)(W_{i+1}(z, b), W_{i+1}(z, c))
+//
+// only needs the sub-circuit's wiring predicates. Copies are padded to a power of two with
+// all-zero inputs.
+#[derive(Debug, Clone)]
+pub struct DataParallelProof<F: PrimeField> {
+    // the outputs of every copy, in the order of the inputs
+    pub outputs: Vec<Vec<F>>,
+    pub sumcheck_proofs: Vec<PartialProof<F>>,
+    pub wb_evals: Vec<F>,
+    pub wc_evals: Vec<F>,
+}
+
+pub fn prove<F: PrimeField>(
+    circuit: &CircuitDescription<F>,
+    inputs: &[Vec<F>],
+) -> DataParallelProof<F> {
+    prove_with_hasher(circuit, inputs, Keccak256::default())
+}
+
+pub fn prove_with_hasher<F: PrimeField, K: HashTrait>(
+    circuit: &CircuitDescription<F>,
+    inputs: &[Vec<F>],
+    hash_function: K,
+) -> DataParallelProof<F> {
+    assert!(!inputs.is_empty(), "expected at least one copy");
+    let copy_bits = copy_bits(inputs.len());
+    let mut copies = inputs.to_vec();
+    copies.resize(1 << copy_bits, vec![F::zero(); circuit.no_of_inputs]);
+    let witnesses: Vec<_> = copies.iter().map(|copy| circuit.evaluate(copy)).collect();
+    // W_i of every copy one after the other, each padded like a single circuit's layer
+    let layer_table = |layer_index| -> Vec<F> {
+        witnesses
+            .iter()
+            .flat_map(|witness| witness.w_i_polynomial(layer_index).coefficients)
+            .collect()
+    };
+
+    let mut transcript: Transcript<K, F> = Transcript::init(hash_function, b"gkr");
+    let w_0_polynomial = MultilinearPolynomial::new(layer_table(0));
+    transcript.append_point(b"w_0", &w_0_polynomial.coefficients);
+    let challenges = transcript.challenge_scalars(b"challenge_a", w_0_polynomial.no_of_variables());
+    let mut claimed_sum = w_0_polynomial.evaluate(&challenges);
+
+    let (r_z, r_a) = challenges.split_at(copy_bits);
+    let mut eq_z = eq_table(r_z);
+    let mut output_weights = eq_table(r_a);
+    let mut sumcheck_proofs = Vec::new();
+    let mut wb_evals = Vec::new();
+    let mut wc_evals = Vec::new();
+
+    for layer_index in 0..circuit.layers.len() {
+        let w = layer_table(layer_index + 1);
+        let sumcheck_proof = partial_prove_data_parallel(
+            circuit,
+            layer_index,
+            w.clone(),
+            eq_z,
+            &output_weights,
+            claimed_sum,
+            &mut transcript,
+        );
+
+        let (z, rb_values, rc_values) =
+            split_challenges(&sumcheck_proof.random_challenges, copy_bits);
+        if layer_index < circuit.layers.len() - 1 {
+            let w = MultilinearPolynomial::new(w);
+            let wb_eval = w.evaluate(&[z, rb_values].concat());
+            let wc_eval = w.evaluate(&[z, rc_values].concat());
+            wb_evals.push(wb_eval);
+            wc_evals.push(wc_eval);
+
+            transcript.append_field(b"wb_eval", &wb_eval);
+            let alpha = transcript.challenge_scalar(b"alpha");
+            transcript.append_field(b"wc_eval", &wc_eval);
+            let beta = transcript.challenge_scalar(b"beta");
+
+            output_weights = eq_table(rb_values)
+                .into_iter()
+                .zip(eq_table(rc_values))
+                .map(|(at_rb, at_rc)| alpha * at_rb + beta * at_rc)
+                .collect();
+            claimed_sum = (alpha * wb_eval) + (beta * wc_eval);
+        }
+        // both claims on the next layer share the copy point
+        eq_z = eq_table(z);
+        sumcheck_proofs.push(sumcheck_proof);
+    }
+
+    DataParallelProof {
+        outputs: witnesses[..inputs.len()]
+            .iter()
+            .map(|witness| witness.outputs().to_vec())
+            .collect(),
+        sumcheck_proofs,
+        wb_evals,
+        wc_evals,
+    }
+}
+
+// The verifier only touches the sub-circuit's gates and the copy variables; the inputs and
+// outputs it reads once each.
+pub fn verify<F: PrimeField>(
+    circuit: &CircuitDescription<F>,
+    inputs: &[Vec<F>],
+    proof: &DataParallelProof<F>,
+) -> Result<(), VerificationError<F>> {
+    verify_with_hasher(circuit, inputs, proof, Keccak256::default())
+}
+
+pub fn verify_with_hasher<F: PrimeField, K: HashTrait>(
+    circuit: &CircuitDescription<F>,
+    inputs: &[Vec<F>],
+    proof: &DataParallelProof<F>,
+    hash_function: K,
+) -> Result<(), VerificationError<F>> {
+    let no_of_layers = circuit.layers.len();
+    let no_of_outputs = circuit.layers[0].no_of_gates();
+    let wrong_output = proof
+        .outputs
+        .iter()
+        .find(|outputs| outputs.len() != no_of_outputs);
+    for (field, expected, received) in [
+        ("outputs", inputs.len(), proof.outputs.len()),
+        (
+            "outputs",
+            no_of_outputs,
+            wrong_output.map_or(no_of_outputs, Vec::len),
+        ),
+        ("sumcheck_proofs", no_of_layers, proof.sumcheck_proofs.len()),
+        (
+            "wb_evals",
+            no_of_layers.saturating_sub(1),
+            proof.wb_evals.len(),
+        ),
+        (
+            "wc_evals",
+            no_of_layers.saturating_sub(1),
+            proof.wc_evals.len(),
+        ),
+    ] {
+        if expected != received {
+            return Err(VerificationError::MalformedProof {
+                field,
+                expected,
+                received,
+            });
+        }
+    }
+
+    let copy_bits = copy_bits(inputs.len());
+    let no_of_copies = 1 << copy_bits;
+    // padded copies run on zero inputs, so their outputs are known without the proof
+    let zeros = vec![F::zero(); circuit.no_of_inputs];
+    let padding_outputs = circuit.evaluate(&zeros).outputs().to_vec();
+    let w_0: Vec<F> = proof
+        .outputs
+        .iter()
+        .chain(std::iter::repeat_n(
+            &padding_outputs,
+            no_of_copies - inputs.len(),
+        ))
+        .flat_map(|outputs| padded_polynomial(outputs.clone()).coefficients)
+        .collect();
+
+    let mut transcript: Transcript<K, F> = Transcript::init(hash_function, b"gkr");
+    let w_0_polynomial = MultilinearPolynomial::new(w_0);
+    transcript.append_point(b"w_0", &w_0_polynomial.coefficients);
+    let challenges = transcript.challenge_scalars(b"challenge_a", w_0_polynomial.no_of_variables());
+    let mut claimed_sum = w_0_polynomial.evaluate(&challenges);
+
+    let (r_z, challenges_a) = challenges.split_at(copy_bits);
+    let mut r_z = r_z.to_vec();
+    let mut alpha = F::zero();
+    let mut beta = F::zero();
+    let mut prev_challenges = Vec::new();
+
+    for layer_index in 0..no_of_layers {
+        let sumcheck_proof = &proof.sumcheck_proofs[layer_index];
+        if claimed_sum != sumcheck_proof.claimed_sum {
+            return Err(VerificationError::ClaimedSumMismatch {
+                expected: claimed_sum,
+                received: sumcheck_proof.claimed_sum,
+            }
+            .at_layer(layer_index));
+        }
+
+        let expected_rounds = copy_bits + 2 * circuit.layer_bits(layer_index + 1);
+        if sumcheck_proof.round_polys.len() != expected_rounds {
+            return Err(VerificationError::MalformedProof {
+                field: "round_polys",
+                expected: expected_rounds,
+                received: sumcheck_proof.round_polys.len(),
+            }
+            .at_layer(layer_index));
+        }
+
+        let sumcheck_verif =
+            partial_verify_data_parallel(sumcheck_proof, copy_bits, &mut transcript)
+                .map_err(|error| error.at_layer(layer_index))?;
+        let (z, rb_values, rc_values) =
+            split_challenges(&sumcheck_verif.random_challenges, copy_bits);
+
+        let (wb_eval, wc_eval) = if layer_index < no_of_layers - 1 {
+            (proof.wb_evals[layer_index], proof.wc_evals[layer_index])
+        } else {
+            let w: Vec<F> = inputs
+                .iter()
+                .chain(std::iter::repeat_n(&zeros, no_of_copies - inputs.len()))
+                .flat_map(|copy| circuit.input_polynomial(copy).coefficients)
+                .collect();
+            let w = MultilinearPolynomial::new(w);
+            (
+                w.evaluate(&[z, rb_values].concat()),
+                w.evaluate(&[z, rc_values].concat()),
+            )
+        };
+
+        let random_challenges = [rb_values, rc_values].concat();
+        let layer_claim = if layer_index == 0 {
+            compute_initial_claim(
+                circuit,
+                layer_index,
+                challenges_a,
+                &random_challenges,
+                wb_eval,
+                wc_eval,
+            )
+        } else {
+            compute_folded_claim(
+                circuit,
+                layer_index,
+                &prev_challenges,
+                &random_challenges,
+                wb_eval,
+                wc_eval,
+                alpha,
+                beta,
+            )
+        };
+        let expected_claim = eq(&r_z, z) * layer_claim;
+
+        if expected_claim != sumcheck_verif.last_claimed_sum {
+            return Err(VerificationError::FinalEvaluationMismatch {
+                expected: expected_claim,
+                received: sumcheck_verif.last_claimed_sum,
+            }
+            .at_layer(layer_index));
+        }
+
+        r_z = z.to_vec();
+        prev_challenges = random_challenges;
+
+        transcript.append_field(b"wb_eval", &wb_eval);
+        alpha = transcript.challenge_scalar(b"alpha");
+        transcript.append_field(b"wc_eval", &wc_eval);
+        beta = transcript.challenge_scalar(b"beta");
+
+        claimed_sum = (alpha * wb_eval) + (beta * wc_eval);
+    }
+
+    Ok(())
+}
+
+fn copy_bits(no_of_copies: usize) -> usize {
+    no_of_copies.next_power_of_two().trailing_zeros() as usize
+}
+
+// the copy point, then rb and rc of a layer's sumcheck challenges
+fn split_challenges<F: PrimeField>(challenges: &[F], copy_bits: usize) -> (&[F], &[F], &[F]) {
+    let (z, rest) = challenges.split_at(copy_bits);
+    let (rb_values, rc_values) = rest.split_at(rest.len() / 2);
+    (z, rb_values, rc_values)
+}
+
+fn eq<F: PrimeField>(x: &[F], y: &[F]) -> F {
+    x.iter()
+        .zip(y)
+        .map(|(x, y)| *x * y + (F::one() - x) * (F::one() - y))
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::CircuitBuilder;
+    use crate::gkr;
+    use ark_bn254::Fq;
+
+    fn to_field(input: Vec<u64>) -> Vec<Fq> {
+        input.into_iter().map(Fq::from).collect()
+    }
+
+    // (x0 + x1) * (x2 - 3) and x0 * 5
+    fn sub_circuit() -> CircuitDescription<Fq> {
+        let mut builder = CircuitBuilder::new();
+        let x = [builder.input(), builder.input(), builder.input()];
+        let sum = builder.add(x[0], x[1]);
+        let three = builder.constant(Fq::from(3));
+        let difference = builder.sub(x[2], three);
+        let output = builder.mul(sum, difference);
+        let scaled = builder.mul_constant(x[0], Fq::from(5));
+        builder.mark_output(output);
+        builder.mark_output(scaled);
+        builder.build()
+    }
+
+    #[test]
+    fn test_data_parallel_gkr() {
+        let circuit = sub_circuit();
+        let inputs: Vec<Vec<Fq>> = (0..5u64).map(|i| to_field(vec![i, i + 1, 2 * i])).collect();
+
+        let proof = prove(&circuit, &inputs);
+        for (copy, outputs) in inputs.iter().zip(&proof.outputs) {
+            assert_eq!(outputs, circuit.evaluate(copy).outputs());
+        }
+        assert!(verify(&circuit, &inputs, &proof).is_ok());
+
+        let mut other_inputs = inputs.clone();
+        other_inputs[3][2] += Fq::from(1);
+        assert!(verify(&circuit, &other_inputs, &proof).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.outputs[4][0] += Fq::from(1);
+        assert!(verify(&circuit, &inputs, &tampered).is_err());
+
+        let mut tampered = proof;
+        tampered.outputs.pop();
+        assert_eq!(
+            verify(&circuit, &inputs, &tampered),
+            Err(VerificationError::MalformedProof {
+                field: "outputs",
+                expected: 5,
+                received: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn test_one_copy_is_plain_gkr() {
+        let circuit = sub_circuit();
+        let inputs = to_field(vec![4, 7, 9]);
+
+        let proof = prove(&circuit, std::slice::from_ref(&inputs));
+        let plain = gkr::prove(&circuit, &inputs);
+        assert_eq!(proof.outputs, vec![plain.outputs]);
+        assert_eq!(proof.wb_evals, plain.wb_evals);
+        for (data_parallel, plain) in proof.sumcheck_proofs.iter().zip(&plain.sumcheck_proofs) {
+            assert_eq!(data_parallel.random_challenges, plain.random_challenges);
+        }
+        assert!(verify(&circuit, &[inputs], &proof).is_ok());
+    }
+}
@@ -1,85 +1,95 @@
-use std::vec;
-
-use ark_ff::{BigInteger, PrimeField};
+use ark_ff::PrimeField;
 use multivariate_poly::MultilinearPolynomial;
 use sha3::Keccak256;
-use sum_check::transcript::Transcript;
+use sum_check::{
+    error::VerificationError,
+    transcript::{HashTrait, Transcript},
+};
 
 use crate::{
-    circuit::Circuit,
-    sumcheck::{partial_prove, partial_verify, PartialProof},
+    circuit::{eq_table, gate_contributions, padded_polynomial, CircuitDescription, Witness},
+    sumcheck::{partial_prove_two_phase, partial_verify, PartialProof},
 };
 
 #[derive(Debug, Clone)]
 pub struct Proof<F: PrimeField> {
+    // the values the circuit claims to compute, what the whole proof is about
+    pub outputs: Vec<F>,
     pub last_claimed_sum: F,
     pub sumcheck_proofs: Vec<PartialProof<F>>,
     pub wb_evals: Vec<F>,
     pub wc_evals: Vec<F>,
 }
 
-pub fn prove<F: PrimeField>(circuit: &mut Circuit<F>) -> Proof<F> {
-    circuit.execute();
+pub fn prove<F: PrimeField>(circuit: &CircuitDescription<F>, inputs: &[F]) -> Proof<F> {
+    prove_with_hasher(circuit, inputs, Keccak256::default())
+}
 
-    let mut transcript: Transcript<Keccak256, F> = Transcript::init(Keccak256::default());
-    let mut w_0_polynomial = circuit.w_i_polynomial(0);
+pub fn prove_with_hasher<F: PrimeField, K: HashTrait>(
+    circuit: &CircuitDescription<F>,
+    inputs: &[F],
+    hash_function: K,
+) -> Proof<F> {
+    let witness = circuit.evaluate(inputs);
+    let mut transcript: Transcript<K, F> = Transcript::init(hash_function, b"gkr");
+    prove_layers(circuit, &witness, &mut transcript)
+}
 
-    if w_0_polynomial.coefficients.len() == 1 {
-        let mut padded_w_0 = w_0_polynomial.coefficients;
-        padded_w_0.push(F::zero());
-        w_0_polynomial = MultilinearPolynomial::new(padded_w_0);
-    }
+// Everything after the transcript is set up, shared with the committed input variant
+pub(crate) fn prove_layers<F: PrimeField, K: HashTrait>(
+    circuit: &CircuitDescription<F>,
+    witness: &Witness<F>,
+    transcript: &mut Transcript<K, F>,
+) -> Proof<F> {
+    // padded with zero gates like every other layer
+    let w_0_polynomial = witness.w_i_polynomial(0);
 
-    transcript.absorb(&w_0_polynomial.convert_to_bytes());
-    let challenge_a = transcript.squeeze();
-    let mut claimed_sum = w_0_polynomial.evaluate(&vec![challenge_a]);
+    transcript.append_point(b"w_0", &w_0_polynomial.coefficients);
+    let challenges_a =
+        transcript.challenge_scalars(b"challenge_a", w_0_polynomial.no_of_variables());
+    let mut claimed_sum = w_0_polynomial.evaluate(&challenges_a);
 
-    let mut f_bc_poly;
     let mut sumcheck_proofs = Vec::new();
     let mut wb_evals = Vec::new();
     let mut wc_evals = Vec::new();
-    let mut alpha = F::zero();
-    let mut beta = F::zero();
-    let mut rb_values = Vec::new();
-    let mut rc_values = Vec::new();
-
-    for layer_index in 0..circuit.outputs.len() {
-        if layer_index == 0 {
-            f_bc_poly = circuit.f_b_c(layer_index, vec![challenge_a], None, None, None, None);
-        } else {
-            f_bc_poly = circuit.f_b_c(
-                layer_index,
-                vec![challenge_a],
-                Some(alpha),
-                Some(beta),
-                Some(&rb_values),
-                Some(&rc_values),
-            );
-        }
+    // the output variables of the next layer's wiring predicates, summed out: eq(a, .) first
+    // and alpha * eq(rb, .) + beta * eq(rc, .) after every layer
+    let mut output_weights = eq_table(&challenges_a);
 
+    for layer_index in 0..circuit.layers.len() {
         // Evaluate wb and wc to be used by verifier
-        let sumcheck_proof = partial_prove(f_bc_poly, claimed_sum, &mut transcript);
+        let sumcheck_proof = partial_prove_two_phase(
+            circuit,
+            witness,
+            layer_index,
+            &output_weights,
+            claimed_sum,
+            transcript,
+        );
         sumcheck_proofs.push(sumcheck_proof.clone());
 
-        if layer_index < circuit.outputs.len() - 1 {
+        if layer_index < circuit.layers.len() - 1 {
             let random_challenges = sumcheck_proof.random_challenges;
-            let w_b = circuit.w_i_polynomial(layer_index + 1);
+            let w_b = witness.w_i_polynomial(layer_index + 1);
             let w_c = w_b.clone();
 
             let (wb_eval, wc_eval) = eval_wb_wc(&w_b, &w_c, &random_challenges);
             wb_evals.push(wb_eval);
             wc_evals.push(wc_eval);
 
-            // use the randomness from the sumcheck proof, split into two vec! for rb and rc
-            let middle = random_challenges.len() / 2;
-            let (new_rb_values, new_rc_values) = random_challenges.split_at(middle);
-            rb_values = new_rb_values.to_vec();
-            rc_values = new_rc_values.to_vec();
+            transcript.append_field(b"wb_eval", &wb_eval);
+            let alpha = transcript.challenge_scalar(b"alpha");
+            transcript.append_field(b"wc_eval", &wc_eval);
+            let beta = transcript.challenge_scalar(b"beta");
 
-            transcript.absorb(&wb_eval.into_bigint().to_bytes_be().as_slice());
-            alpha = transcript.squeeze();
-            transcript.absorb(&wc_eval.into_bigint().to_bytes_be().as_slice());
-            beta = transcript.squeeze();
+            // use the randomness from the sumcheck proof, split into rb and rc
+            let middle = random_challenges.len() / 2;
+            let (rb_values, rc_values) = random_challenges.split_at(middle);
+            output_weights = eq_table(rb_values)
+                .into_iter()
+                .zip(eq_table(rc_values))
+                .map(|(at_rb, at_rc)| alpha * at_rb + beta * at_rc)
+                .collect();
 
             // Compute claimed sum using linear combination form
             claimed_sum = (alpha * wb_eval) + (beta * wc_eval);
@@ -87,6 +97,7 @@ pub fn prove<F: PrimeField>(circuit: &mut Circuit<F>) -> Proof<F> {
     }
 
     Proof {
+        outputs: witness.outputs().to_vec(),
         last_claimed_sum: claimed_sum,
         sumcheck_proofs,
         wb_evals,
@@ -94,44 +105,110 @@ pub fn prove<F: PrimeField>(circuit: &mut Circuit<F>) -> Proof<F> {
     }
 }
 
-pub fn verify<F: PrimeField>(proof: Proof<F>, circuit: &mut Circuit<F>) -> bool {
-    let mut transcript: Transcript<Keccak256, F> = Transcript::init(Keccak256::default());
-    let mut w_0_polynomial = circuit.w_i_polynomial(0);
+// Checks that the circuit maps `inputs` to `proof.outputs`; the verifier never evaluates the
+// circuit itself.
+pub fn verify<F: PrimeField>(
+    circuit: &CircuitDescription<F>,
+    inputs: &[F],
+    proof: &Proof<F>,
+) -> Result<(), VerificationError<F>> {
+    verify_with_hasher(circuit, inputs, proof, Keccak256::default())
+}
+
+pub fn verify_with_hasher<F: PrimeField, K: HashTrait>(
+    circuit: &CircuitDescription<F>,
+    inputs: &[F],
+    proof: &Proof<F>,
+    hash_function: K,
+) -> Result<(), VerificationError<F>> {
+    let mut transcript: Transcript<K, F> = Transcript::init(hash_function, b"gkr");
+    verify_layers(circuit, proof, &mut transcript, |random_challenges| {
+        let w_b = circuit.input_polynomial(inputs);
+        let w_c = w_b.clone();
+        Ok(eval_wb_wc(&w_b, &w_c, random_challenges))
+    })
+}
 
-    if w_0_polynomial.coefficients.len() == 1 {
-        let mut padded_w_0 = w_0_polynomial.coefficients;
-        padded_w_0.push(F::zero());
-        w_0_polynomial = MultilinearPolynomial::new(padded_w_0);
+// Checks every layer, asking `input_evals` for W of the input layer at the last layer's
+// (rb, rc): evaluated from the inputs, or opened from a commitment to them.
+pub(crate) fn verify_layers<F: PrimeField, K: HashTrait>(
+    circuit: &CircuitDescription<F>,
+    proof: &Proof<F>,
+    transcript: &mut Transcript<K, F>,
+    mut input_evals: impl FnMut(&Vec<F>) -> Result<(F, F), VerificationError<F>>,
+) -> Result<(), VerificationError<F>> {
+    let no_of_layers = circuit.layers.len();
+    for (field, expected, received) in [
+        (
+            "outputs",
+            circuit.layers[0].no_of_gates(),
+            proof.outputs.len(),
+        ),
+        ("sumcheck_proofs", no_of_layers, proof.sumcheck_proofs.len()),
+        (
+            "wb_evals",
+            no_of_layers.saturating_sub(1),
+            proof.wb_evals.len(),
+        ),
+        (
+            "wc_evals",
+            no_of_layers.saturating_sub(1),
+            proof.wc_evals.len(),
+        ),
+    ] {
+        if expected != received {
+            return Err(VerificationError::MalformedProof {
+                field,
+                expected,
+                received,
+            });
+        }
     }
 
-    transcript.absorb(&w_0_polynomial.convert_to_bytes());
-    let challenge_a = transcript.squeeze();
-    let mut claimed_sum = w_0_polynomial.evaluate(&vec![challenge_a]);
+    // padded with zero gates like every other layer
+    let w_0_polynomial = padded_polynomial(proof.outputs.clone());
+
+    transcript.append_point(b"w_0", &w_0_polynomial.coefficients);
+    let challenges_a =
+        transcript.challenge_scalars(b"challenge_a", w_0_polynomial.no_of_variables());
+    let mut claimed_sum = w_0_polynomial.evaluate(&challenges_a);
 
     let mut alpha = F::zero();
     let mut beta = F::zero();
     let mut prev_challenges = Vec::new();
 
-    for layer_index in 0..circuit.outputs.len() {
-        if claimed_sum != proof.sumcheck_proofs[layer_index].claimed_sum {
-            return false;
+    for layer_index in 0..no_of_layers {
+        let sumcheck_proof = &proof.sumcheck_proofs[layer_index];
+        if claimed_sum != sumcheck_proof.claimed_sum {
+            return Err(VerificationError::ClaimedSumMismatch {
+                expected: claimed_sum,
+                received: sumcheck_proof.claimed_sum,
+            }
+            .at_layer(layer_index));
         }
 
-        let sumcheck_verif = partial_verify(&proof.sumcheck_proofs[layer_index], &mut transcript);
-        if !sumcheck_verif.is_proof_valid {
-            return false;
+        let expected_rounds = 2 * circuit.layer_bits(layer_index + 1);
+        if sumcheck_proof.round_polys.len() != expected_rounds {
+            return Err(VerificationError::MalformedProof {
+                field: "round_polys",
+                expected: expected_rounds,
+                received: sumcheck_proof.round_polys.len(),
+            }
+            .at_layer(layer_index));
         }
 
+        let sumcheck_verif = partial_verify(sumcheck_proof, transcript)
+            .map_err(|error| error.at_layer(layer_index))?;
+
         let wb_eval;
         let wc_eval;
         let random_challenges = sumcheck_verif.random_challenges;
 
-        if layer_index < circuit.outputs.len() - 1 {
+        if layer_index < no_of_layers - 1 {
             (wb_eval, wc_eval) = (proof.wb_evals[layer_index], proof.wc_evals[layer_index]);
         } else {
-            let w_b = MultilinearPolynomial::new(circuit.inputs.clone());
-            let w_c = w_b.clone();
-            (wb_eval, wc_eval) = eval_wb_wc(&w_b, &w_c, &random_challenges);
+            (wb_eval, wc_eval) =
+                input_evals(&random_challenges).map_err(|error| error.at_layer(layer_index))?;
         }
 
         let expected_claim;
@@ -140,7 +217,7 @@ pub fn verify<F: PrimeField>(proof: Proof<F>, circuit: &mut Circuit<F>) -> bool
             expected_claim = compute_initial_claim(
                 circuit,
                 layer_index,
-                challenge_a,
+                &challenges_a,
                 &random_challenges,
                 wb_eval,
                 wc_eval,
@@ -159,21 +236,25 @@ pub fn verify<F: PrimeField>(proof: Proof<F>, circuit: &mut Circuit<F>) -> bool
         };
 
         if expected_claim != sumcheck_verif.last_claimed_sum {
-            return false;
+            return Err(VerificationError::FinalEvaluationMismatch {
+                expected: expected_claim,
+                received: sumcheck_verif.last_claimed_sum,
+            }
+            .at_layer(layer_index));
         }
 
         prev_challenges = random_challenges;
 
-        transcript.absorb(&wb_eval.into_bigint().to_bytes_be().as_slice());
-        alpha = transcript.squeeze();
+        transcript.append_field(b"wb_eval", &wb_eval);
+        alpha = transcript.challenge_scalar(b"alpha");
 
-        transcript.absorb(wc_eval.into_bigint().to_bytes_be().as_slice());
-        beta = transcript.squeeze();
+        transcript.append_field(b"wc_eval", &wc_eval);
+        beta = transcript.challenge_scalar(b"beta");
 
         claimed_sum = (alpha * wb_eval) + (beta * wc_eval);
     }
 
-    true
+    Ok(())
 }
 
 pub fn eval_wb_wc<F: PrimeField>(
@@ -191,27 +272,23 @@ pub fn eval_wb_wc<F: PrimeField>(
 }
 
 pub fn compute_initial_claim<F: PrimeField>(
-    circuit: &mut Circuit<F>,
+    circuit: &CircuitDescription<F>,
     layer_index: usize,
-    challenge_a: F,
+    challenges_a: &[F],
     random_challenges: &Vec<F>,
     wb_eval: F,
     wc_eval: F,
 ) -> F {
-    let (add_i_poly, mul_i_poly) = circuit.add_i_n_mul_i_arrays(layer_index);
-    let (add_i_bc, mul_i_bc) = (
-        add_i_poly.partial_evaluate(0, challenge_a),
-        mul_i_poly.partial_evaluate(0, challenge_a),
-    );
-
-    let add_r = add_i_bc.evaluate(random_challenges);
-    let mul_r = mul_i_bc.evaluate(random_challenges);
+    let middle = random_challenges.len() / 2;
+    let (challenge_rb, challenge_rc) = random_challenges.split_at(middle);
+    let predicates =
+        circuit.evaluate_wiring_predicates(layer_index, challenges_a, challenge_rb, challenge_rc);
 
-    (add_r * (wb_eval + wc_eval)) + (mul_r * (wb_eval * wc_eval))
+    gate_contributions(&predicates, wb_eval, wc_eval)
 }
 
 pub fn compute_folded_claim<F: PrimeField>(
-    circuit: &mut Circuit<F>,
+    circuit: &CircuitDescription<F>,
     layer_index: usize,
     prev_challenges: &Vec<F>,
     random_challenges: &Vec<F>,
@@ -221,26 +298,28 @@ pub fn compute_folded_claim<F: PrimeField>(
     beta: F,
 ) -> F {
     let mid = prev_challenges.len() / 2;
-    let (challenge_rb, challenge_rc) = prev_challenges.split_at(mid);
-
-    let (new_add_i, new_mul_i) = circuit.alpha_beta_add_n_mul_bc(
-        alpha,
-        beta,
-        &challenge_rb.to_vec(),
-        &challenge_rc.to_vec(),
-        layer_index,
-    );
-
-    let add_r = new_add_i.evaluate(random_challenges);
-    let mul_r = new_mul_i.evaluate(random_challenges);
-
-    (add_r * (wb_eval + wc_eval)) + (mul_r * (wb_eval * wc_eval))
+    let (prev_rb, prev_rc) = prev_challenges.split_at(mid);
+    let middle = random_challenges.len() / 2;
+    let (challenge_rb, challenge_rc) = random_challenges.split_at(middle);
+
+    // alpha * P(prev_rb, rb, rc) + beta * P(prev_rc, rb, rc) for every predicate P
+    let at_rb =
+        circuit.evaluate_wiring_predicates(layer_index, prev_rb, challenge_rb, challenge_rc);
+    let at_rc =
+        circuit.evaluate_wiring_predicates(layer_index, prev_rc, challenge_rb, challenge_rc);
+    let predicates: Vec<F> = at_rb
+        .into_iter()
+        .zip(at_rc)
+        .map(|(at_rb, at_rc)| alpha * at_rb + beta * at_rc)
+        .collect();
+
+    gate_contributions(&predicates, wb_eval, wc_eval)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::circuit::{Gate, Layer};
+    use crate::circuit::{Gate, Layer, Op};
     use ark_bn254::Fq;
 
     fn to_field(input: Vec<u64>) -> Vec<Fq> {
@@ -251,24 +330,164 @@ mod tests {
     fn test_gkr() {
         let inputs = to_field(vec![1, 2, 3, 4, 5, 6, 7, 8]);
 
-        let gate_1: Gate = Gate::new('+', 0, 1, 0);
-        let gate_2: Gate = Gate::new('*', 2, 3, 1);
-        let gate_3: Gate = Gate::new('*', 4, 5, 2);
-        let gate_4: Gate = Gate::new('*', 6, 7, 3);
+        let gate_1 = Gate::new(Op::Add, 0, 1, 0);
+        let gate_2 = Gate::new(Op::Mul, 2, 3, 1);
+        let gate_3 = Gate::new(Op::Mul, 4, 5, 2);
+        let gate_4 = Gate::new(Op::Mul, 6, 7, 3);
 
-        let gate_5: Gate = Gate::new('+', 0, 1, 0);
-        let gate_6: Gate = Gate::new('*', 2, 3, 1);
+        let gate_5 = Gate::new(Op::Add, 0, 1, 0);
+        let gate_6 = Gate::new(Op::Mul, 2, 3, 1);
 
-        let gate_7: Gate = Gate::new('+', 0, 1, 0);
+        let gate_7 = Gate::new(Op::Add, 0, 1, 0);
 
         let layer_0 = Layer::init(vec![gate_7]);
         let layer_1 = Layer::init(vec![gate_5, gate_6]);
         let layer_2 = Layer::init(vec![gate_1, gate_2, gate_3, gate_4]);
 
-        let mut circuit = Circuit::create(inputs, vec![layer_0, layer_1, layer_2]);
-        let proof = prove(&mut circuit);
+        let circuit = CircuitDescription::new(vec![layer_0, layer_1, layer_2], 8);
+        let proof = prove(&circuit, &inputs);
         dbg!(&proof);
-        let verify = verify(proof, &mut circuit);
-        assert_eq!(verify, true);
+        let verify = verify(&circuit, &inputs, &proof);
+        assert!(verify.is_ok());
+    }
+
+    #[test]
+    fn test_gkr_richer_gates() {
+        let inputs = to_field(vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let layer_0 = Layer::init(vec![Gate::new(Op::Add, 0, 1, 0)]);
+        let layer_1 = Layer::init(vec![
+            Gate::new(Op::Mul, 0, 1, 0),
+            Gate::new(Op::Sub, 2, 3, 1),
+        ]);
+        let layer_2 = Layer::init(vec![
+            Gate::new(Op::Sub, 0, 1, 0),
+            Gate::new(Op::ConstMul(Fq::from(3)), 2, 2, 1),
+            Gate::new(Op::Relay, 4, 4, 2),
+            Gate::new(Op::Const(Fq::from(11)), 0, 0, 3),
+        ]);
+
+        let circuit = CircuitDescription::new(vec![layer_0, layer_1, layer_2], 8);
+        let proof = prove(&circuit, &inputs);
+        assert_eq!(proof.outputs, vec![-Fq::from(15)]);
+        assert!(verify(&circuit, &inputs, &proof).is_ok());
+
+        // the relayed input is read by the verifier through the relay predicate
+        let mut other_inputs = inputs;
+        other_inputs[4] += Fq::from(1);
+        assert!(verify(&circuit, &other_inputs, &proof).is_err());
+    }
+
+    #[test]
+    fn test_gkr_rejects_wrong_outputs() {
+        let inputs = to_field(vec![1, 2, 3, 4]);
+        let layer_0 = Layer::init(vec![Gate::new(Op::Add, 0, 1, 0)]);
+        let layer_1 = Layer::init(vec![
+            Gate::new(Op::Add, 0, 1, 0),
+            Gate::new(Op::Mul, 2, 3, 1),
+        ]);
+        let circuit = CircuitDescription::new(vec![layer_0, layer_1], 4);
+
+        // one description, many witnesses
+        for inputs in [inputs.clone(), to_field(vec![5, 6, 7, 8])] {
+            let proof = prove(&circuit, &inputs);
+            assert!(verify(&circuit, &inputs, &proof).is_ok());
+        }
+
+        let mut proof = prove(&circuit, &inputs);
+        proof.outputs[0] += Fq::from(1);
+        assert!(verify(&circuit, &inputs, &proof).is_err());
+
+        proof.outputs.push(Fq::from(0));
+        assert_eq!(
+            verify(&circuit, &inputs, &proof),
+            Err(VerificationError::MalformedProof {
+                field: "outputs",
+                expected: 1,
+                received: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_gkr_reports_failing_layer() {
+        let inputs = to_field(vec![1, 2, 3, 4]);
+        let layer_0 = Layer::init(vec![Gate::new(Op::Add, 0, 1, 0)]);
+        let layer_1 = Layer::init(vec![
+            Gate::new(Op::Add, 0, 1, 0),
+            Gate::new(Op::Mul, 2, 3, 1),
+        ]);
+
+        let circuit = CircuitDescription::new(vec![layer_0, layer_1], 4);
+        let mut proof = prove(&circuit, &inputs);
+        proof.wb_evals[0] += Fq::from(1);
+
+        match verify(&circuit, &inputs, &proof) {
+            Err(VerificationError::Layer { layer, error }) => {
+                assert_eq!(layer, 0);
+                assert!(matches!(
+                    *error,
+                    VerificationError::FinalEvaluationMismatch { .. }
+                ));
+            }
+            result => panic!("expected a layer 0 error, got {result:?}"),
+        }
+
+        proof.wb_evals.pop();
+        assert_eq!(
+            verify(&circuit, &inputs, &proof),
+            Err(VerificationError::MalformedProof {
+                field: "wb_evals",
+                expected: 1,
+                received: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_gkr_rejects_truncated_sumcheck() {
+        let inputs = to_field(vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let layer_0 = Layer::init(vec![Gate::new(Op::Add, 0, 1, 0)]);
+        let layer_1 = Layer::init(vec![
+            Gate::new(Op::Add, 0, 1, 0),
+            Gate::new(Op::Mul, 2, 3, 1),
+        ]);
+        let layer_2 = Layer::init(vec![
+            Gate::new(Op::Add, 0, 1, 0),
+            Gate::new(Op::Mul, 2, 3, 1),
+            Gate::new(Op::Mul, 4, 5, 2),
+            Gate::new(Op::Mul, 6, 7, 3),
+        ]);
+
+        let circuit = CircuitDescription::new(vec![layer_0, layer_1, layer_2], 8);
+        let mut proof = prove(&circuit, &inputs);
+        proof.sumcheck_proofs[1].round_polys.pop();
+        assert_eq!(
+            verify(&circuit, &inputs, &proof),
+            Err(VerificationError::MalformedProof {
+                field: "round_polys",
+                expected: 4,
+                received: 3,
+            }
+            .at_layer(1))
+        );
+    }
+
+    #[cfg(all(feature = "sha2", feature = "blake2"))]
+    #[test]
+    fn test_gkr_rejects_other_hash_backend() {
+        use sum_check::transcript::{Blake2s256, Sha256};
+
+        let inputs = to_field(vec![1, 2, 3, 4]);
+        let layer_0 = Layer::init(vec![Gate::new(Op::Add, 0, 1, 0)]);
+        let layer_1 = Layer::init(vec![
+            Gate::new(Op::Add, 0, 1, 0),
+            Gate::new(Op::Mul, 2, 3, 1),
+        ]);
+
+        let circuit = CircuitDescription::new(vec![layer_0, layer_1], 4);
+        let proof = prove_with_hasher(&circuit, &inputs, Sha256::default());
+        assert!(verify_with_hasher(&circuit, &inputs, &proof, Sha256::default()).is_ok());
+        assert!(verify_with_hasher(&circuit, &inputs, &proof, Blake2s256::default()).is_err());
     }
 }
@@ -1,3 +1,7 @@
+pub mod bristol;
+pub mod builder;
 pub mod circuit;
+pub mod committed;
+pub mod data_parallel;
 pub mod gkr;
 pub mod sumcheck;
@@ -0,0 +1,31 @@
+use std::fmt;
+
+// Why an opening was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KZGError {
+    // the proof or the point has the wrong number of entries for the setup
+    MalformedProof {
+        field: &'static str,
+        expected: usize,
+        received: usize,
+    },
+    PairingCheckFailed,
+}
+
+impl fmt::Display for KZGError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KZGError::MalformedProof {
+                field,
+                expected,
+                received,
+            } => write!(
+                f,
+                "malformed proof: {field} has {received} entries, expected {expected}"
+            ),
+            KZGError::PairingCheckFailed => write!(f, "pairing check failed"),
+        }
+    }
+}
+
+impl std::error::Error for KZGError {}
@@ -1,3 +1,4 @@
+pub mod error;
 pub mod trusted_setup;
 
 use ark_ec::{
@@ -5,6 +6,7 @@ use ark_ec::{
     pairing::{Pairing, PairingOutput},
 };
 use ark_ff::{AdditiveGroup, PrimeField, Zero};
+use error::KZGError;
 use multivariate_poly::MultilinearPolynomial;
 use trusted_setup::TrustedSetup;
 
@@ -13,7 +15,7 @@ pub struct KZG<F: PrimeField, P: Pairing> {
     pub setup: TrustedSetup<P>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct KZGProof<F: PrimeField, P: Pairing> {
     pub commitment: P::G1,
     pub poly_opened: F,
@@ -26,72 +28,101 @@ impl<F: PrimeField, P: Pairing> KZG<F, P> {
     }
 
     pub fn prove(&self, open_vals: &Vec<F>) -> KZGProof<F, P> {
-        // commit poly
-        let commitment = commit::<F, P>(&self.setup.g1_taus, &self.poly.coefficients);
+        open(&self.setup, &self.poly, open_vals)
+    }
+
+    pub fn verify(&self, proof: KZGProof<F, P>, open_vals: &Vec<F>) -> Result<(), KZGError> {
+        verify_opening(&self.setup, &proof, open_vals)
+    }
+}
+
+// The prover and verifier halves of KZG for callers that keep the setup and the polynomial
+// apart, like a verifier that only ever sees the commitment.
+pub fn open<F: PrimeField, P: Pairing>(
+    setup: &TrustedSetup<P>,
+    poly: &MultilinearPolynomial<F>,
+    open_vals: &[F],
+) -> KZGProof<F, P> {
+    // commit poly
+    let commitment = commit::<F, P>(&setup.g1_taus, &poly.coefficients);
+
+    // open poly
+    let v = poly.evaluate(&open_vals.to_vec());
+
+    // compute poly minus v
+    let poly_minus_v = poly.coefficients.iter().map(|coeff| *coeff - v).collect();
+    let mut sub_poly = MultilinearPolynomial::new(poly_minus_v);
 
-        // open poly
-        let v = self.poly.evaluate(&open_vals);
+    let mut quotient_evals = Vec::with_capacity(open_vals.len());
 
-        // compute poly minus v
-        let poly_minus_v = self
-            .poly
-            .coefficients
+    for i in 0..open_vals.len() {
+        let quotient_poly = compute_quotient(&sub_poly);
+        let blown_quotient_poly = blow_up(quotient_poly, i + 1);
+
+        let quotient_eval = setup
+            .g1_taus
             .iter()
-            .map(|coeff| *coeff - v)
-            .collect();
-        let mut sub_poly = MultilinearPolynomial::new(poly_minus_v);
-
-        let mut quotient_evals = Vec::with_capacity(open_vals.len());
-
-        for i in 0..open_vals.len() {
-            let quotient_poly = compute_quotient(&sub_poly);
-            let blown_quotient_poly = blow_up(quotient_poly, i + 1);
-
-            let quotient_eval = self
-                .setup
-                .g1_taus
-                .iter()
-                .zip(blown_quotient_poly.coefficients.iter())
-                .map(|(g1_taus, coeffs)| g1_taus.mul_bigint(coeffs.into_bigint()))
-                .sum();
-            quotient_evals.push(quotient_eval);
-
-            let remainder = sub_poly.partial_evaluate(0, open_vals[i]);
-            sub_poly = remainder;
-        }
-        assert_eq!(sub_poly.coefficients[0], F::zero());
+            .zip(blown_quotient_poly.coefficients.iter())
+            .map(|(g1_taus, coeffs)| g1_taus.mul_bigint(coeffs.into_bigint()))
+            .sum();
+        quotient_evals.push(quotient_eval);
 
-        KZGProof {
-            commitment,
-            poly_opened: v,
-            quotient_evals,
-        }
+        let remainder = sub_poly.partial_evaluate(0, open_vals[i]);
+        sub_poly = remainder;
     }
+    assert_eq!(sub_poly.coefficients[0], F::zero());
 
-    pub fn verify(&self, proof: KZGProof<F, P>, open_vals: &Vec<F>) -> bool {
-        let g1_generator = P::G1::generator();
-        let g2_generator = P::G2::generator();
+    KZGProof {
+        commitment,
+        poly_opened: v,
+        quotient_evals,
+    }
+}
 
-        // pairing(g1_(f(τ) - v), g2_1) == pairing(Σ(g1_Q(τ), g2_(τ - a)))
+pub fn verify_opening<F: PrimeField, P: Pairing>(
+    setup: &TrustedSetup<P>,
+    proof: &KZGProof<F, P>,
+    open_vals: &[F],
+) -> Result<(), KZGError> {
+    for (field, expected, received) in [
+        (
+            "quotient_evals",
+            open_vals.len(),
+            proof.quotient_evals.len(),
+        ),
+        ("open_vals", setup.g2_taus.len(), open_vals.len()),
+    ] {
+        if expected != received {
+            return Err(KZGError::MalformedProof {
+                field,
+                expected,
+                received,
+            });
+        }
+    }
 
-        let lhs = P::pairing(
-            proof.commitment - g1_generator.mul_bigint(proof.poly_opened.into_bigint()),
-            g2_generator.mul_bigint(F::one().into_bigint()),
-        );
+    let g1_generator = P::G1::generator();
+    let g2_generator = P::G2::generator();
 
-        let mut rhs = PairingOutput::ZERO;
-        for (i, tau) in self.setup.g2_taus.iter().enumerate() {
-            rhs += P::pairing(
-                proof.quotient_evals[i],
-                *tau - g2_generator.mul_bigint(open_vals[i].into_bigint()),
-            );
-        }
+    // pairing(g1_(f(τ) - v), g2_1) == pairing(Σ(g1_Q(τ), g2_(τ - a)))
 
-        dbg!(&lhs);
-        dbg!(&rhs);
+    let lhs = P::pairing(
+        proof.commitment - g1_generator.mul_bigint(proof.poly_opened.into_bigint()),
+        g2_generator.mul_bigint(F::one().into_bigint()),
+    );
 
-        lhs == rhs
+    let mut rhs = PairingOutput::ZERO;
+    for (i, tau) in setup.g2_taus.iter().enumerate() {
+        rhs += P::pairing(
+            proof.quotient_evals[i],
+            *tau - g2_generator.mul_bigint(open_vals[i].into_bigint()),
+        );
     }
+
+    if lhs != rhs {
+        return Err(KZGError::PairingCheckFailed);
+    }
+    Ok(())
 }
 
 pub fn commit<F: PrimeField, P: Pairing>(g1_taus: &Vec<<P>::G1>, poly_coeffs: &Vec<F>) -> P::G1 {
@@ -175,9 +206,9 @@ pub mod tests {
         dbg!(&proof);
 
         let verify = kzg.verify(proof, &open_vals);
-        dbg!(verify);
+        dbg!(&verify);
 
-        assert_eq!(verify, true);
+        assert!(verify.is_ok());
     }
 
     #[test]
@@ -202,8 +233,24 @@ pub mod tests {
         dbg!(&proof);
 
         let verify = kzg.verify(proof, &open_vals);
-        dbg!(verify);
+        dbg!(&verify);
+
+        assert!(verify.is_ok());
+    }
 
-        assert_eq!(verify, true);
+    #[test]
+    fn test_verify_rejects_wrong_opening() {
+        let taus = vec![Fr::from(5), Fr::from(2), Fr::from(3)];
+        let setup = TrustedSetup::<Bls12_381>::initialize(&taus);
+        let values = (1..=8u64).map(Fr::from).collect();
+        let kzg = KZG::init(MultilinearPolynomial::new(values), setup);
+
+        let open_vals = vec![Fr::from(6), Fr::from(4), Fr::from(0)];
+        let mut proof = kzg.prove(&open_vals);
+        proof.poly_opened += Fr::from(1);
+        assert_eq!(
+            kzg.verify(proof, &open_vals),
+            Err(KZGError::PairingCheckFailed)
+        );
     }
 }
@@ -207,6 +207,32 @@ pub fn tensor_mul<F: PrimeField>(
     MultilinearPolynomial::new(mul_poly)
 }
 
+// eq(x, r) = Π (x_i * r_i + (1 - x_i) * (1 - r_i)), laid out over the hypercube with the
+// first variable as the most significant bit (same ordering partial_evaluate(0, _) folds)
+pub fn eq_polynomial<F: PrimeField>(point: &[F]) -> MultilinearPolynomial<F> {
+    let mut evals = vec![F::one()];
+    for r in point {
+        evals = evals
+            .iter()
+            .flat_map(|eval| [*eval * (F::one() - r), *eval * r])
+            .collect();
+    }
+    MultilinearPolynomial::new(evals)
+}
+
+// evaluates eq(a, b) directly in O(n) without building the hypercube table
+pub fn eq_evaluate<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    assert_eq!(
+        a.len(),
+        b.len(),
+        "points must have the same number of variables"
+    );
+    a.iter()
+        .zip(b.iter())
+        .map(|(a_i, b_i)| *a_i * b_i + (F::one() - a_i) * (F::one() - b_i))
+        .product()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -362,4 +388,18 @@ mod tests {
         let expected = MultilinearPolynomial::new(vec![Fq::from(4), Fq::from(6)]);
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_eq_polynomial() {
+        let point = vec![Fq::from(1), Fq::from(0)];
+        let eq_poly = eq_polynomial(&point);
+        // eq(x, (1, 0)) is 1 only on the hypercube point 10
+        let expected = vec![Fq::from(0), Fq::from(0), Fq::from(1), Fq::from(0)];
+        assert_eq!(eq_poly.coefficients, expected);
+
+        let point = vec![Fq::from(3), Fq::from(5), Fq::from(7)];
+        let eval_at = vec![Fq::from(2), Fq::from(4), Fq::from(6)];
+        let eq_poly = eq_polynomial(&point);
+        assert_eq!(eq_poly.evaluate(&eval_at), eq_evaluate(&point, &eval_at));
+    }
 }
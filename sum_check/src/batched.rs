@@ -0,0 +1,272 @@
+use crate::{
+    error::VerificationError,
+    prover::{fold_in_place, split_and_sum},
+    transcript::{HashTrait, Transcript},
+};
+use ark_ff::PrimeField;
+use multivariate_poly::MultilinearPolynomial;
+use sha3::Keccak256;
+
+// Batched sumcheck: proves several claims Σ p_i(x) = c_i at once by running a single sumcheck
+// on Σ ρ_i * p_i, with the ρ_i squeezed from the transcript.
+// Polynomials with fewer variables than the largest one are padded by repeating their
+// evaluations, which makes them independent of the leading variables and scales their sum
+// by 2^(n - n_i). The round polynomials are the same size no matter how many claims are
+// batched; poly_evals carries one evaluation claim per polynomial for the caller to check.
+
+#[derive(Debug, Clone)]
+pub struct BatchedProof<F: PrimeField> {
+    pub claimed_sums: Vec<F>,
+    pub round_polys: Vec<[F; 2]>,
+    pub poly_evals: Vec<F>,
+}
+
+// p_i(point) == evaluation, where point is the suffix of the sumcheck challenges that
+// matches p_i's own number of variables
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchedSubClaim<F: PrimeField> {
+    pub point: Vec<F>,
+    pub evaluation: F,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchedProver<F: PrimeField, K: HashTrait = Keccak256> {
+    pub polys: Vec<MultilinearPolynomial<F>>,
+    pub claimed_sums: Vec<F>,
+    pub transcript: Transcript<K, F>,
+}
+
+impl<F: PrimeField> BatchedProver<F> {
+    pub fn new(polys_eval_points: &[Vec<F>], claimed_sums: &[F]) -> Self {
+        Self::with_hasher(polys_eval_points, claimed_sums, Keccak256::default())
+    }
+}
+
+impl<F: PrimeField, K: HashTrait> BatchedProver<F, K> {
+    pub fn with_hasher(polys_eval_points: &[Vec<F>], claimed_sums: &[F], hash_function: K) -> Self {
+        assert_eq!(
+            polys_eval_points.len(),
+            claimed_sums.len(),
+            "every polynomial needs a claimed sum"
+        );
+        Self {
+            polys: polys_eval_points
+                .iter()
+                .map(|evals| MultilinearPolynomial::new(evals.clone()))
+                .collect(),
+            claimed_sums: claimed_sums.to_vec(),
+            transcript: Transcript::init(hash_function, b"batched_sumcheck"),
+        }
+    }
+
+    pub fn prove(&mut self) -> BatchedProof<F> {
+        let no_of_variables: Vec<usize> = self.polys.iter().map(|p| p.no_of_variables()).collect();
+        let max_variables = no_of_variables.iter().copied().max().unwrap_or(0);
+
+        let coefficients =
+            absorb_claims(&mut self.transcript, &no_of_variables, &self.claimed_sums);
+
+        // Σ ρ_i * p_i over the largest hypercube
+        let mut combined = vec![F::zero(); 1 << max_variables];
+        for (poly, rho) in self.polys.iter().zip(coefficients.iter()) {
+            let size = poly.coefficients.len();
+            for (i, value) in combined.iter_mut().enumerate() {
+                *value += *rho * poly.coefficients[i % size];
+            }
+        }
+
+        let mut round_polys = Vec::with_capacity(max_variables);
+        let mut challenges = Vec::with_capacity(max_variables);
+
+        for _ in 0..max_variables {
+            let round_poly_coeffs = split_and_sum(&combined);
+            self.transcript
+                .append_point(b"round_poly", &round_poly_coeffs);
+            round_polys.push(round_poly_coeffs);
+
+            let challenge: F = self.transcript.challenge_scalar(b"challenge");
+            fold_in_place(&mut combined, challenge);
+            challenges.push(challenge);
+        }
+
+        let poly_evals = self
+            .polys
+            .iter()
+            .map(|poly| {
+                let offset = max_variables - poly.no_of_variables();
+                poly.evaluate(&challenges[offset..].to_vec())
+            })
+            .collect();
+
+        BatchedProof {
+            claimed_sums: self.claimed_sums.clone(),
+            round_polys,
+            poly_evals,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchedVerify<F: PrimeField, K: HashTrait = Keccak256> {
+    pub transcript: Transcript<K, F>,
+    pub no_of_variables: Vec<usize>,
+}
+
+impl<F: PrimeField> BatchedVerify<F> {
+    pub fn new(no_of_variables: &[usize]) -> Self {
+        Self::with_hasher(no_of_variables, Keccak256::default())
+    }
+}
+
+impl<F: PrimeField, K: HashTrait> BatchedVerify<F, K> {
+    pub fn with_hasher(no_of_variables: &[usize], hash_function: K) -> Self {
+        Self {
+            transcript: Transcript::init(hash_function, b"batched_sumcheck"),
+            no_of_variables: no_of_variables.to_vec(),
+        }
+    }
+
+    pub fn verify(
+        &mut self,
+        proof: BatchedProof<F>,
+    ) -> Result<Vec<BatchedSubClaim<F>>, VerificationError<F>> {
+        let no_of_claims = self.no_of_variables.len();
+        let max_variables = self.no_of_variables.iter().copied().max().unwrap_or(0);
+        for (field, expected, received) in [
+            ("claimed_sums", no_of_claims, proof.claimed_sums.len()),
+            ("poly_evals", no_of_claims, proof.poly_evals.len()),
+            ("round_polys", max_variables, proof.round_polys.len()),
+        ] {
+            if expected != received {
+                return Err(VerificationError::MalformedProof {
+                    field,
+                    expected,
+                    received,
+                });
+            }
+        }
+
+        let coefficients = absorb_claims(
+            &mut self.transcript,
+            &self.no_of_variables,
+            &proof.claimed_sums,
+        );
+
+        let mut claimed_sum: F = self
+            .no_of_variables
+            .iter()
+            .zip(proof.claimed_sums.iter())
+            .zip(coefficients.iter())
+            .map(|((n, sum), rho)| *rho * sum * F::from(1u64 << (max_variables - n)))
+            .sum();
+        let mut challenges = Vec::with_capacity(max_variables);
+
+        for (round, round_poly) in proof.round_polys.into_iter().enumerate() {
+            let round_sum: F = round_poly.iter().sum();
+            if claimed_sum != round_sum {
+                return Err(VerificationError::RoundSumMismatch {
+                    round,
+                    expected: claimed_sum,
+                    received: round_sum,
+                });
+            }
+            self.transcript.append_point(b"round_poly", &round_poly);
+            let challenge: F = self.transcript.challenge_scalar(b"challenge");
+            claimed_sum = round_poly[0] + challenge * (round_poly[1] - round_poly[0]);
+            challenges.push(challenge);
+        }
+
+        let combined_eval: F = proof
+            .poly_evals
+            .iter()
+            .zip(coefficients.iter())
+            .map(|(eval, rho)| *eval * rho)
+            .sum();
+        if claimed_sum != combined_eval {
+            return Err(VerificationError::FinalEvaluationMismatch {
+                expected: claimed_sum,
+                received: combined_eval,
+            });
+        }
+
+        Ok(self
+            .no_of_variables
+            .iter()
+            .zip(proof.poly_evals)
+            .map(|(n, evaluation)| BatchedSubClaim {
+                point: challenges[max_variables - n..].to_vec(),
+                evaluation,
+            })
+            .collect())
+    }
+}
+
+// binds every claim (its size and claimed sum) and squeezes one batching coefficient per claim
+fn absorb_claims<F: PrimeField, K: HashTrait>(
+    transcript: &mut Transcript<K, F>,
+    no_of_variables: &[usize],
+    claimed_sums: &[F],
+) -> Vec<F> {
+    let mut coefficients = Vec::with_capacity(claimed_sums.len());
+    for (n, sum) in no_of_variables.iter().zip(claimed_sums.iter()) {
+        transcript.append_message(b"no_of_variables", &(*n as u64).to_be_bytes());
+        transcript.append_field(b"claimed_sum", sum);
+        let rho: F = transcript.challenge_scalar(b"batching_coefficient");
+        coefficients.push(rho);
+    }
+    coefficients
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fq;
+
+    fn to_field(input: Vec<u64>) -> Vec<Fq> {
+        input.into_iter().map(Fq::from).collect()
+    }
+
+    fn sum(input: &[Fq]) -> Fq {
+        input.iter().sum()
+    }
+
+    #[test]
+    fn test_batched_sumcheck() {
+        let polys = vec![
+            to_field(vec![0, 0, 0, 3, 0, 0, 2, 5]),
+            to_field(vec![1, 2, 3, 4]),
+            to_field(vec![7, 9]),
+        ];
+        let claimed_sums: Vec<Fq> = polys.iter().map(|p| sum(p)).collect();
+
+        let mut prover = BatchedProver::new(&polys, &claimed_sums);
+        let proof = prover.prove();
+        assert_eq!(proof.round_polys.len(), 3);
+
+        let mut verifier = BatchedVerify::new(&[3, 2, 1]);
+        let sub_claims = verifier.verify(proof).expect("batched proof should verify");
+
+        for (poly, sub_claim) in polys.into_iter().zip(sub_claims) {
+            let poly = MultilinearPolynomial::new(poly);
+            assert_eq!(poly.evaluate(&sub_claim.point), sub_claim.evaluation);
+        }
+    }
+
+    #[test]
+    fn test_batched_sumcheck_rejects_wrong_claim() {
+        let polys = vec![
+            to_field(vec![0, 0, 0, 3, 0, 0, 2, 5]),
+            to_field(vec![1, 2, 3, 4]),
+        ];
+        let claimed_sums = vec![Fq::from(10), Fq::from(11)];
+
+        let mut prover = BatchedProver::new(&polys, &claimed_sums);
+        let proof = prover.prove();
+
+        let mut verifier = BatchedVerify::new(&[3, 2]);
+        assert!(matches!(
+            verifier.verify(proof),
+            Err(VerificationError::RoundSumMismatch { round: 0, .. })
+        ));
+    }
+}
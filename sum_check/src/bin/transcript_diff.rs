@@ -0,0 +1,31 @@
+// Points at the first absorb or squeeze where a prover and a verifier transcript log disagree.
+// usage: transcript_diff <prover.log> <verifier.log>
+use std::{env, fs, process};
+use sum_check::transcript::recording::{diff, TranscriptLog};
+
+fn read_log(path: &str) -> TranscriptLog {
+    let input = fs::read_to_string(path).unwrap_or_else(|error| {
+        eprintln!("{path}: {error}");
+        process::exit(2);
+    });
+    TranscriptLog::parse(&input).unwrap_or_else(|error| {
+        eprintln!("{path}: {error}");
+        process::exit(2);
+    })
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("usage: {} <prover.log> <verifier.log>", args[0]);
+        process::exit(2);
+    }
+
+    match diff(&read_log(&args[1]), &read_log(&args[2])) {
+        Some(divergence) => {
+            println!("{divergence}");
+            process::exit(1);
+        }
+        None => println!("transcripts match"),
+    }
+}
@@ -0,0 +1,112 @@
+use ark_ff::Field;
+use std::fmt;
+
+// Why a verifier rejected a proof. Rounds and layers are counted from zero.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationError<F: Field> {
+    // the proof claims a different sum than the one being checked
+    ClaimedSumMismatch {
+        expected: F,
+        received: F,
+    },
+    // g(0) + g(1) of a round polynomial doesn't match the running claim
+    RoundSumMismatch {
+        round: usize,
+        expected: F,
+        received: F,
+    },
+    // a round polynomial has a higher degree than the protocol allows
+    RoundDegreeTooHigh {
+        round: usize,
+        max_degree: usize,
+        received: usize,
+    },
+    // the claim left after the last round doesn't match the polynomial's evaluation
+    FinalEvaluationMismatch {
+        expected: F,
+        received: F,
+    },
+    // some part of the proof has the wrong number of entries
+    MalformedProof {
+        field: &'static str,
+        expected: usize,
+        received: usize,
+    },
+    // an interactive message arrived out of order
+    UnexpectedMessage {
+        round: usize,
+    },
+    PairingCheckFailed,
+    // a commitment opening is for a different commitment than the one being checked
+    CommitmentMismatch,
+    // the grinding nonce doesn't give the required number of leading zero bits
+    InsufficientProofOfWork {
+        bits: u32,
+    },
+    // the error happened while checking one layer of a layered proof (GKR)
+    Layer {
+        layer: usize,
+        error: Box<VerificationError<F>>,
+    },
+}
+
+impl<F: Field> VerificationError<F> {
+    pub fn at_layer(self, layer: usize) -> Self {
+        VerificationError::Layer {
+            layer,
+            error: Box::new(self),
+        }
+    }
+}
+
+impl<F: Field> fmt::Display for VerificationError<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::ClaimedSumMismatch { expected, received } => write!(
+                f,
+                "claimed sum mismatch: expected {expected}, received {received}"
+            ),
+            VerificationError::RoundSumMismatch {
+                round,
+                expected,
+                received,
+            } => write!(
+                f,
+                "round {round}: g(0) + g(1) is {received}, expected {expected}"
+            ),
+            VerificationError::RoundDegreeTooHigh {
+                round,
+                max_degree,
+                received,
+            } => write!(
+                f,
+                "round {round}: round polynomial has degree {received}, at most {max_degree} allowed"
+            ),
+            VerificationError::FinalEvaluationMismatch { expected, received } => write!(
+                f,
+                "final evaluation mismatch: expected {expected}, received {received}"
+            ),
+            VerificationError::MalformedProof {
+                field,
+                expected,
+                received,
+            } => write!(
+                f,
+                "malformed proof: {field} has {received} entries, expected {expected}"
+            ),
+            VerificationError::UnexpectedMessage { round } => {
+                write!(f, "round {round}: unexpected message")
+            }
+            VerificationError::PairingCheckFailed => write!(f, "pairing check failed"),
+            VerificationError::CommitmentMismatch => {
+                write!(f, "opening doesn't match the commitment")
+            }
+            VerificationError::InsufficientProofOfWork { bits } => {
+                write!(f, "proof of work doesn't have {bits} leading zero bits")
+            }
+            VerificationError::Layer { layer, error } => write!(f, "layer {layer}: {error}"),
+        }
+    }
+}
+
+impl<F: Field> std::error::Error for VerificationError<F> {}
@@ -0,0 +1,185 @@
+use crate::{
+    error::VerificationError,
+    prover::{fold_in_place, split_and_sum},
+    transcript::{HashTrait, Transcript},
+    verifier::{check_proof_shape, SubClaim},
+};
+use ark_ff::{Field, PrimeField};
+use multivariate_poly::MultilinearPolynomial;
+
+// Sumcheck on a polynomial over a small base field F with challenges drawn from an extension E
+// of F, so soundness comes from |E| instead of |F|. The first round only sums base field
+// evaluations; folding with the first challenge is what moves the table into E, and the
+// remaining rounds run there. The final point is in E, so the sub-claim is an evaluation of
+// the base field polynomial at an extension point.
+
+#[derive(Debug, Clone)]
+pub struct ExtensionProof<F: PrimeField, E: Field<BasePrimeField = F>> {
+    pub claimed_sum: F,
+    pub round_polys: Vec<[E; 2]>,
+}
+
+pub fn prove<F: PrimeField, E: Field<BasePrimeField = F>, K: HashTrait>(
+    poly: &MultilinearPolynomial<F>,
+    claimed_sum: F,
+    transcript: &mut Transcript<K, F>,
+) -> ExtensionProof<F, E> {
+    transcript.append_field(b"claimed_sum", &claimed_sum);
+
+    let no_of_variables = poly.no_of_variables();
+    let mut round_polys = Vec::with_capacity(no_of_variables);
+    if no_of_variables == 0 {
+        return ExtensionProof {
+            claimed_sum,
+            round_polys,
+        };
+    }
+
+    // first round in the base field
+    let [eval_0, eval_1] = split_and_sum(&poly.coefficients);
+    let round_poly = [
+        E::from_base_prime_field(eval_0),
+        E::from_base_prime_field(eval_1),
+    ];
+    transcript.append_extension(b"round_poly", &round_poly);
+    round_polys.push(round_poly);
+    let challenge: E = transcript.challenge_extension(b"challenge");
+
+    let (low, high) = poly.coefficients.split_at(poly.coefficients.len() / 2);
+    let mut table: Vec<E> = low
+        .iter()
+        .zip(high.iter())
+        .map(|(low, high)| {
+            challenge.mul_by_base_prime_field(&(*high - low)) + E::from_base_prime_field(*low)
+        })
+        .collect();
+
+    for _ in 1..no_of_variables {
+        let round_poly = split_and_sum(&table);
+        transcript.append_extension(b"round_poly", &round_poly);
+        round_polys.push(round_poly);
+
+        let challenge: E = transcript.challenge_extension(b"challenge");
+        fold_in_place(&mut table, challenge);
+    }
+
+    ExtensionProof {
+        claimed_sum,
+        round_polys,
+    }
+}
+
+pub fn verify<F: PrimeField, E: Field<BasePrimeField = F>, K: HashTrait>(
+    proof: &ExtensionProof<F, E>,
+    no_of_variables: usize,
+    claimed_sum: F,
+    transcript: &mut Transcript<K, F>,
+) -> Result<SubClaim<E>, VerificationError<E>> {
+    check_proof_shape(
+        E::from_base_prime_field(proof.claimed_sum),
+        proof.round_polys.len(),
+        no_of_variables,
+        E::from_base_prime_field(claimed_sum),
+    )?;
+
+    transcript.append_field(b"claimed_sum", &claimed_sum);
+    let mut claimed_sum = E::from_base_prime_field(claimed_sum);
+    let mut challenges = Vec::with_capacity(no_of_variables);
+
+    for (round, round_poly) in proof.round_polys.iter().enumerate() {
+        let round_sum = round_poly[0] + round_poly[1];
+        if claimed_sum != round_sum {
+            return Err(VerificationError::RoundSumMismatch {
+                round,
+                expected: claimed_sum,
+                received: round_sum,
+            });
+        }
+        transcript.append_extension(b"round_poly", round_poly);
+        let challenge: E = transcript.challenge_extension(b"challenge");
+        claimed_sum = round_poly[0] + challenge * (round_poly[1] - round_poly[0]);
+        challenges.push(challenge);
+    }
+
+    Ok(SubClaim {
+        point: challenges,
+        expected_value: claimed_sum,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::{
+        fields::{Fp64, MontBackend, MontConfig},
+        Fp2, Fp2Config, MontFp,
+    };
+    use sha3::Keccak256;
+
+    // Mersenne-31 and its quadratic extension F[i] / (i^2 + 1)
+    #[derive(MontConfig)]
+    #[modulus = "2147483647"]
+    #[generator = "7"]
+    pub struct M31Config;
+    type M31 = Fp64<MontBackend<M31Config, 1>>;
+
+    pub struct M31Ext2Config;
+    impl Fp2Config for M31Ext2Config {
+        type Fp = M31;
+        const NONRESIDUE: M31 = MontFp!("-1");
+        const FROBENIUS_COEFF_FP2_C1: &'static [M31] = &[MontFp!("1"), MontFp!("-1")];
+    }
+    type M31Ext2 = Fp2<M31Ext2Config>;
+
+    fn to_field(input: Vec<u64>) -> Vec<M31> {
+        input.into_iter().map(M31::from).collect()
+    }
+
+    #[test]
+    fn test_extension_sumcheck() {
+        let poly = MultilinearPolynomial::new(to_field(vec![0, 0, 0, 3, 0, 0, 2, 5]));
+
+        let mut prover_transcript =
+            Transcript::<Keccak256, M31>::init(Keccak256::default(), b"sumcheck");
+        let proof: ExtensionProof<M31, M31Ext2> =
+            prove(&poly, M31::from(10), &mut prover_transcript);
+        // the first round never leaves the base field
+        assert!(proof.round_polys[0]
+            .iter()
+            .all(|eval| eval.c1 == M31::from(0)));
+
+        let mut verifier_transcript =
+            Transcript::<Keccak256, M31>::init(Keccak256::default(), b"sumcheck");
+        let sub_claim = verify(&proof, 3, M31::from(10), &mut verifier_transcript)
+            .expect("sumcheck should verify");
+        assert!(sub_claim.point.iter().all(|r| r.c1 != M31::from(0)));
+
+        // evaluate the base field polynomial at the extension point
+        let mut table: Vec<M31Ext2> = poly
+            .coefficients
+            .iter()
+            .map(|eval| M31Ext2::from_base_prime_field(*eval))
+            .collect();
+        for r in &sub_claim.point {
+            fold_in_place(&mut table, *r);
+        }
+        assert_eq!(table[0], sub_claim.expected_value);
+    }
+
+    #[test]
+    fn test_extension_sumcheck_rejects_wrong_sum() {
+        let poly = MultilinearPolynomial::new(to_field(vec![0, 0, 0, 3, 0, 0, 2, 5]));
+
+        let mut prover_transcript =
+            Transcript::<Keccak256, M31>::init(Keccak256::default(), b"sumcheck");
+        let proof: ExtensionProof<M31, M31Ext2> =
+            prove(&poly, M31::from(11), &mut prover_transcript);
+
+        let mut verifier_transcript =
+            Transcript::<Keccak256, M31>::init(Keccak256::default(), b"sumcheck");
+        assert!(matches!(
+            verify(&proof, 3, M31::from(11), &mut verifier_transcript),
+            Err(VerificationError::RoundSumMismatch { round: 0, .. })
+        ));
+    }
+}
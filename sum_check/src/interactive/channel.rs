@@ -0,0 +1,194 @@
+use ark_ff::{BigInteger, PrimeField};
+use std::{
+    io::{self, Read, Write},
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+// Messages of the interactive sumcheck. The prover sends the claimed sum and one round
+// polynomial per round, the verifier answers each round polynomial with a challenge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Message<F: PrimeField> {
+    ClaimedSum(F),
+    RoundPoly([F; 2]),
+    Challenge(F),
+}
+
+impl<F: PrimeField> Message<F> {
+    // tag byte followed by the field elements, big-endian
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (tag, elements) = match self {
+            Message::ClaimedSum(sum) => (0, vec![*sum]),
+            Message::RoundPoly(round_poly) => (1, round_poly.to_vec()),
+            Message::Challenge(challenge) => (2, vec![*challenge]),
+        };
+        let mut bytes = vec![tag];
+        for element in elements {
+            bytes.extend(element.into_bigint().to_bytes_be());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let (tag, body) = bytes
+            .split_first()
+            .ok_or_else(|| invalid("empty message"))?;
+        let element_size = element_size::<F>();
+        if body.len() % element_size != 0 {
+            return Err(invalid("truncated field element"));
+        }
+        let elements = body
+            .chunks(element_size)
+            .map(|chunk| {
+                let element = F::from_be_bytes_mod_order(chunk);
+                // reject non-canonical encodings
+                if element.into_bigint().to_bytes_be() != chunk {
+                    return Err(invalid("field element out of range"));
+                }
+                Ok(element)
+            })
+            .collect::<io::Result<Vec<F>>>()?;
+
+        match (tag, elements.as_slice()) {
+            (0, [sum]) => Ok(Message::ClaimedSum(*sum)),
+            (1, [eval_0, eval_1]) => Ok(Message::RoundPoly([*eval_0, *eval_1])),
+            (2, [challenge]) => Ok(Message::Challenge(*challenge)),
+            _ => Err(invalid("unknown message")),
+        }
+    }
+}
+
+// the longest encoding, a round polynomial
+fn max_message_len<F: PrimeField>() -> usize {
+    1 + 2 * element_size::<F>()
+}
+
+fn element_size<F: PrimeField>() -> usize {
+    F::zero().into_bigint().to_bytes_be().len()
+}
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+pub trait Channel<F: PrimeField> {
+    fn send(&mut self, message: Message<F>) -> io::Result<()>;
+    fn receive(&mut self) -> io::Result<Message<F>>;
+}
+
+// One end of an in-memory pipe; the ends can be moved to different threads
+#[derive(Debug)]
+pub struct MemoryChannel<F: PrimeField> {
+    sender: Sender<Message<F>>,
+    receiver: Receiver<Message<F>>,
+}
+
+impl<F: PrimeField> MemoryChannel<F> {
+    pub fn pair() -> (Self, Self) {
+        let (sender_a, receiver_b) = channel();
+        let (sender_b, receiver_a) = channel();
+        (
+            Self {
+                sender: sender_a,
+                receiver: receiver_a,
+            },
+            Self {
+                sender: sender_b,
+                receiver: receiver_b,
+            },
+        )
+    }
+}
+
+impl<F: PrimeField> Channel<F> for MemoryChannel<F> {
+    fn send(&mut self, message: Message<F>) -> io::Result<()> {
+        self.sender
+            .send(message)
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+
+    fn receive(&mut self) -> io::Result<Message<F>> {
+        self.receiver
+            .recv()
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+}
+
+// Length-prefixed messages over any byte stream, e.g. a TcpStream or a UnixStream
+#[derive(Debug)]
+pub struct StreamChannel<S: Read + Write> {
+    stream: S,
+}
+
+impl<S: Read + Write> StreamChannel<S> {
+    pub fn new(stream: S) -> Self {
+        Self { stream }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<F: PrimeField, S: Read + Write> Channel<F> for StreamChannel<S> {
+    fn send(&mut self, message: Message<F>) -> io::Result<()> {
+        let bytes = message.to_bytes();
+        self.stream.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        self.stream.write_all(&bytes)?;
+        self.stream.flush()
+    }
+
+    fn receive(&mut self) -> io::Result<Message<F>> {
+        let mut len = [0u8; 4];
+        self.stream.read_exact(&mut len)?;
+        // the length comes from the peer, so don't allocate more than a message can need
+        let len = u32::from_le_bytes(len) as usize;
+        if len > max_message_len::<F>() {
+            return Err(invalid("message too long"));
+        }
+        let mut bytes = vec![0u8; len];
+        self.stream.read_exact(&mut bytes)?;
+        Message::from_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fq;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_message_encoding() {
+        let messages = [
+            Message::ClaimedSum(Fq::from(29)),
+            Message::RoundPoly([Fq::from(2), Fq::from(27)]),
+            Message::Challenge(-Fq::from(1)),
+        ];
+        for message in messages {
+            assert_eq!(Message::from_bytes(&message.to_bytes()).unwrap(), message);
+        }
+
+        let mut truncated = Message::Challenge(Fq::from(5)).to_bytes();
+        truncated.pop();
+        assert!(Message::<Fq>::from_bytes(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_stream_channel_rejects_long_messages() {
+        let mut channel = StreamChannel::new(Cursor::new(Vec::new()));
+        Channel::<Fq>::send(
+            &mut channel,
+            Message::RoundPoly([Fq::from(2), Fq::from(27)]),
+        )
+        .unwrap();
+        channel.stream.write_all(&u32::MAX.to_le_bytes()).unwrap();
+        channel.stream.set_position(0);
+
+        assert_eq!(
+            Channel::<Fq>::receive(&mut channel).unwrap(),
+            Message::RoundPoly([Fq::from(2), Fq::from(27)])
+        );
+        let error = Channel::<Fq>::receive(&mut channel).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,116 +1,137 @@
-use crate::prover::split_and_sum;
-use ark_ff::PrimeField;
-use multivariate_poly::{partial_evaluate, MultilinearPolynomial};
+pub mod channel;
+pub mod prover;
+pub mod verifier;
 
-#[derive(Debug, Clone)]
-struct Prover<F: PrimeField> {
-    claimed_sum: F,
-    univariate_poly: [F; 2],
-}
-
-struct Verifier<F: PrimeField> {
-    challenges: Vec<F>,
-    initial_poly: MultilinearPolynomial<F>,
-}
+pub use channel::{Channel, MemoryChannel, Message, StreamChannel};
+pub use prover::InteractiveProver;
+pub use verifier::InteractiveVerifier;
 
-struct SumCheck<F: PrimeField> {
-    poly: MultilinearPolynomial<F>,
-    verifier: Verifier<F>,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fq;
+    use multivariate_poly::MultilinearPolynomial;
 
-#[allow(dead_code)]
-impl<F: PrimeField> SumCheck<F> {
-    fn init(poly: MultilinearPolynomial<F>) -> Self {
-        Self {
-            poly: poly.clone(),
-            verifier: Verifier {
-                challenges: vec![],
-                initial_poly: poly,
-            },
-        }
+    fn to_field(input: Vec<u64>) -> Vec<Fq> {
+        input.into_iter().map(Fq::from).collect()
     }
 
-    fn prove(&mut self, claimed_sum: F) -> Prover<F> {
-        let mut poly_coeff = self.poly.coefficients.clone();
-        if self.verifier.challenges.len() > 0 {
-            dbg!("now check challenge");
-            poly_coeff = partial_evaluate(
-                self.poly.coefficients.to_vec(),
-                0,
-                self.verifier.challenges[0],
-            );
-            self.poly.coefficients = poly_coeff.clone();
-        }
-        let round_poly = split_and_sum(&poly_coeff);
-
-        Prover {
-            claimed_sum,
-            univariate_poly: round_poly,
-        }
+    fn run_over<C: Channel<Fq> + Send + 'static>(
+        prover_end: C,
+        mut verifier_end: C,
+        evals: Vec<Fq>,
+        prover_sum: Fq,
+        claimed_sum: Fq,
+    ) -> (
+        InteractiveVerifier<Fq>,
+        Result<crate::verifier::SubClaim<Fq>, crate::error::VerificationError<Fq>>,
+    ) {
+        let prover = std::thread::spawn(move || {
+            let mut prover_end = prover_end;
+            InteractiveProver::new(&evals, prover_sum).run(&mut prover_end)
+        });
+        let mut verifier = InteractiveVerifier::new(3, claimed_sum);
+        let sub_claim = verifier
+            .run(&mut verifier_end, &mut ark_std::test_rng())
+            .unwrap();
+        // a rejecting verifier hangs up early, so the prover may see a closed channel
+        drop(verifier_end);
+        let _ = prover.join().unwrap();
+        (verifier, sub_claim)
     }
 
-    fn verify(&mut self, prover: Prover<F>, challenge: F) -> bool {
-        let round_poly = prover.univariate_poly;
-        dbg!(round_poly);
-        dbg!(prover.claimed_sum);
-        if prover.claimed_sum != round_poly.iter().sum() {
-            return false;
-        }
-        self.verifier.challenges.insert(0, challenge);
+    #[test]
+    fn test_interactive_memory_channel() {
+        let evals = to_field(vec![0, 0, 0, 2, 0, 10, 0, 17]);
+        let (prover_end, verifier_end) = MemoryChannel::pair();
+        let (verifier, sub_claim) = run_over(
+            prover_end,
+            verifier_end,
+            evals.clone(),
+            Fq::from(29),
+            Fq::from(29),
+        );
 
-        if self.verifier.challenges.len() == self.verifier.initial_poly.no_of_variables() {
-            dbg!("last round!");
-            let verifier_sum =
-                round_poly[0] + self.verifier.challenges[0] * (round_poly[1] - round_poly[0]);
-            self.verifier.challenges.reverse();
-            let total_sum = self
-                .verifier
-                .initial_poly
-                .evaluate(&self.verifier.challenges);
-            dbg!(verifier_sum);
-            dbg!(total_sum);
-            if verifier_sum != total_sum {
-                return false;
-            }
-        }
+        let sub_claim = sub_claim.expect("honest prover should be accepted");
+        let poly = MultilinearPolynomial::new(evals);
+        assert_eq!(poly.evaluate(&sub_claim.point), sub_claim.expected_value);
 
-        true
+        // the recorded run replays to the same sub-claim, a tampered one doesn't
+        assert_eq!(
+            InteractiveVerifier::replay(3, Fq::from(29), &verifier.transcript),
+            Ok(sub_claim)
+        );
+        let mut tampered = verifier.transcript.clone();
+        tampered[0] = Message::ClaimedSum(Fq::from(30));
+        assert!(InteractiveVerifier::replay(3, Fq::from(29), &tampered).is_err());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use ark_bn254::Fq;
+    #[test]
+    fn test_interactive_rejects_wrong_sum() {
+        let evals = to_field(vec![0, 0, 0, 2, 0, 10, 0, 17]);
+        let (prover_end, verifier_end) = MemoryChannel::pair();
+        // the prover's round polynomials really add up to 29, so only the claimed sum gives it
+        // away when the verifier expects 30
+        let (_, sub_claim) = run_over(prover_end, verifier_end, evals, Fq::from(30), Fq::from(30));
+        assert_eq!(
+            sub_claim,
+            Err(crate::error::VerificationError::RoundSumMismatch {
+                round: 0,
+                expected: Fq::from(30),
+                received: Fq::from(29),
+            })
+        );
+    }
 
-    pub(crate) fn to_field(input: Vec<u64>) -> Vec<Fq> {
-        input.into_iter().map(|v| Fq::from(v)).collect()
+    #[test]
+    fn test_interactive_rejects_wrong_claimed_sum() {
+        // a prover claiming some other total than the one the verifier expects
+        let evals = to_field(vec![0, 0, 0, 2, 0, 10, 0, 17]);
+        let (prover_end, verifier_end) = MemoryChannel::pair();
+        let (verifier, sub_claim) =
+            run_over(prover_end, verifier_end, evals, Fq::from(30), Fq::from(29));
+        assert_eq!(
+            sub_claim,
+            Err(crate::error::VerificationError::ClaimedSumMismatch {
+                expected: Fq::from(29),
+                received: Fq::from(30),
+            })
+        );
+        assert!(verifier.transcript.is_empty());
     }
 
     #[test]
-    fn test_sum_check() {
-        let poly = MultilinearPolynomial::new(to_field(vec![0, 0, 0, 2, 0, 10, 0, 17]));
-        let mut sum_check = SumCheck::init(poly);
-        let prover = sum_check.prove(Fq::from(29));
-        let verifier = sum_check.verify(prover, Fq::from(5));
-        dbg!(verifier);
-        let prover_1 = sum_check.prove(Fq::from(127));
-        let verifier_1 = sum_check.verify(prover_1, Fq::from(3));
-        dbg!(verifier_1);
-        let prover_2 = sum_check.prove(Fq::from(131));
-        let verifier_2 = sum_check.verify(prover_2, Fq::from(2));
-        dbg!(verifier_2);
-        // check if all true
-        assert!(verifier && verifier_1 && verifier_2);
+    fn test_interactive_tcp_channel() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let prover_end = StreamChannel::new(std::net::TcpStream::connect(address).unwrap());
+        let verifier_end = StreamChannel::new(listener.accept().unwrap().0);
 
-        let poly = MultilinearPolynomial::new(to_field(vec![0, 2, 0, 5]));
-        let mut sum_check = SumCheck::init(poly);
-        let prover = sum_check.prove(Fq::from(7));
-        let verifier = sum_check.verify(prover, Fq::from(5));
-        dbg!(verifier);
-        let prover_1 = sum_check.prove(Fq::from(17));
-        let verifier_1 = sum_check.verify(prover_1, Fq::from(2));
-        dbg!(verifier_1);
-        assert!(verifier && verifier_1);
+        let evals = to_field(vec![0, 0, 0, 2, 0, 10, 0, 17]);
+        let (_, sub_claim) = run_over(
+            prover_end,
+            verifier_end,
+            evals.clone(),
+            Fq::from(29),
+            Fq::from(29),
+        );
+        let sub_claim = sub_claim.expect("honest prover should be accepted");
+        let poly = MultilinearPolynomial::new(evals);
+        assert_eq!(poly.evaluate(&sub_claim.point), sub_claim.expected_value);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_interactive_unix_channel() {
+        let (prover_end, verifier_end) = std::os::unix::net::UnixStream::pair().unwrap();
+        let evals = to_field(vec![0, 0, 0, 2, 0, 10, 0, 17]);
+        let (_, sub_claim) = run_over(
+            StreamChannel::new(prover_end),
+            StreamChannel::new(verifier_end),
+            evals,
+            Fq::from(29),
+            Fq::from(29),
+        );
+        assert!(sub_claim.is_ok());
     }
 }
@@ -0,0 +1,50 @@
+use super::channel::{Channel, Message};
+use crate::prover::{fold_in_place, split_and_sum};
+use ark_ff::PrimeField;
+use std::io;
+
+// Prover side of the interactive sumcheck: answers every challenge with the next round
+// polynomial, folding its evaluation table as it goes.
+#[derive(Debug, Clone)]
+pub struct InteractiveProver<F: PrimeField> {
+    pub claimed_sum: F,
+    table: Vec<F>,
+}
+
+impl<F: PrimeField> InteractiveProver<F> {
+    pub fn new(poly_eval_points: &[F], claimed_sum: F) -> Self {
+        Self {
+            claimed_sum,
+            table: poly_eval_points.to_vec(),
+        }
+    }
+
+    pub fn rounds_left(&self) -> usize {
+        self.table.len().trailing_zeros() as usize
+    }
+
+    pub fn round_poly(&self) -> Message<F> {
+        Message::RoundPoly(split_and_sum(&self.table))
+    }
+
+    pub fn receive_challenge(&mut self, challenge: F) {
+        fold_in_place(&mut self.table, challenge);
+    }
+
+    pub fn run<C: Channel<F>>(&mut self, channel: &mut C) -> io::Result<()> {
+        channel.send(Message::ClaimedSum(self.claimed_sum))?;
+        while self.rounds_left() > 0 {
+            channel.send(self.round_poly())?;
+            match channel.receive()? {
+                Message::Challenge(challenge) => self.receive_challenge(challenge),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "expected a challenge",
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+}
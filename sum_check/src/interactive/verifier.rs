@@ -0,0 +1,137 @@
+use super::channel::{Channel, Message};
+use crate::{error::VerificationError, verifier::SubClaim};
+use ark_ff::PrimeField;
+use ark_std::rand::Rng;
+use std::io;
+
+// Verifier side of the interactive sumcheck. It is given the sum it expects up front and the
+// prover's opening claim has to match it. Every message it sends or receives is kept in
+// `transcript`, so a finished run can be checked again later with `replay`.
+#[derive(Debug, Clone)]
+pub struct InteractiveVerifier<F: PrimeField> {
+    pub no_of_variables: usize,
+    pub challenges: Vec<F>,
+    pub transcript: Vec<Message<F>>,
+    claimed_sum: F,
+    // the sum the next round polynomial has to add up to, once the prover has claimed one
+    current_sum: Option<F>,
+    pending_round_poly: Option<[F; 2]>,
+}
+
+impl<F: PrimeField> InteractiveVerifier<F> {
+    pub fn new(no_of_variables: usize, claimed_sum: F) -> Self {
+        Self {
+            no_of_variables,
+            challenges: Vec::with_capacity(no_of_variables),
+            transcript: Vec::new(),
+            claimed_sum,
+            current_sum: None,
+            pending_round_poly: None,
+        }
+    }
+
+    pub fn receive(&mut self, message: Message<F>) -> Result<(), VerificationError<F>> {
+        let round = self.challenges.len();
+        match (message, self.current_sum) {
+            (Message::ClaimedSum(sum), None) => {
+                if sum != self.claimed_sum {
+                    return Err(VerificationError::ClaimedSumMismatch {
+                        expected: self.claimed_sum,
+                        received: sum,
+                    });
+                }
+                self.current_sum = Some(sum);
+            }
+            (Message::RoundPoly(round_poly), Some(claimed_sum))
+                if self.pending_round_poly.is_none() && !self.is_done() =>
+            {
+                let round_sum = round_poly[0] + round_poly[1];
+                if round_sum != claimed_sum {
+                    return Err(VerificationError::RoundSumMismatch {
+                        round,
+                        expected: claimed_sum,
+                        received: round_sum,
+                    });
+                }
+                self.pending_round_poly = Some(round_poly);
+            }
+            _ => return Err(VerificationError::UnexpectedMessage { round }),
+        }
+        self.transcript.push(message);
+        Ok(())
+    }
+
+    // answers the last round polynomial with `challenge`
+    pub fn challenge(&mut self, challenge: F) -> Result<Message<F>, VerificationError<F>> {
+        let round_poly =
+            self.pending_round_poly
+                .take()
+                .ok_or(VerificationError::UnexpectedMessage {
+                    round: self.challenges.len(),
+                })?;
+        self.current_sum = Some(round_poly[0] + challenge * (round_poly[1] - round_poly[0]));
+        self.challenges.push(challenge);
+
+        let message = Message::Challenge(challenge);
+        self.transcript.push(message);
+        Ok(message)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.challenges.len() == self.no_of_variables
+    }
+
+    // what is left for the caller: the polynomial must evaluate to expected_value at point
+    pub fn sub_claim(&self) -> Result<SubClaim<F>, VerificationError<F>> {
+        match self.current_sum {
+            Some(expected_value) if self.is_done() => Ok(SubClaim {
+                point: self.challenges.clone(),
+                expected_value,
+            }),
+            _ => Err(VerificationError::MalformedProof {
+                field: "challenges",
+                expected: self.no_of_variables,
+                received: self.challenges.len(),
+            }),
+        }
+    }
+
+    // the outer error is a channel failure, the inner one a rejected proof
+    pub fn run<C: Channel<F>, R: Rng>(
+        &mut self,
+        channel: &mut C,
+        rng: &mut R,
+    ) -> io::Result<Result<SubClaim<F>, VerificationError<F>>> {
+        if let Err(error) = self.receive(channel.receive()?) {
+            return Ok(Err(error));
+        }
+        while !self.is_done() {
+            if let Err(error) = self.receive(channel.receive()?) {
+                return Ok(Err(error));
+            }
+            let challenge = self
+                .challenge(F::rand(rng))
+                .expect("a round polynomial was just received");
+            channel.send(challenge)?;
+        }
+        Ok(self.sub_claim())
+    }
+
+    // re-runs the checks of a recorded transcript with the challenges it contains
+    pub fn replay(
+        no_of_variables: usize,
+        claimed_sum: F,
+        transcript: &[Message<F>],
+    ) -> Result<SubClaim<F>, VerificationError<F>> {
+        let mut verifier = Self::new(no_of_variables, claimed_sum);
+        for message in transcript {
+            match message {
+                Message::Challenge(challenge) => {
+                    verifier.challenge(*challenge)?;
+                }
+                _ => verifier.receive(*message)?,
+            }
+        }
+        verifier.sub_claim()
+    }
+}
@@ -1,12 +1,26 @@
+pub mod batched;
+pub mod error;
+pub mod extension;
 pub mod interactive;
+pub mod poseidon;
 pub mod prover;
+pub mod streaming;
 pub mod transcript;
 pub mod verifier;
+pub mod zerocheck;
+pub mod zk;
 
 #[cfg(test)]
 mod test {
-    use crate::{prover::Prover, verifier::Verify};
-    use field_tracker::{end_tscope, print_summary, start_tscope, Ft};
+    use crate::{
+        prover::{split_and_sum, Prover},
+        transcript::Transcript,
+        verifier::Verify,
+    };
+    use ark_ff::PrimeField;
+    use field_tracker::{end_tscope, print_summary, start_tscope, summary, Ft};
+    use multivariate_poly::MultilinearPolynomial;
+    use sha3::Keccak256;
     type Fq = Ft!(ark_bn254::Fq);
     // use ark_bn254::Fq;
 
@@ -24,11 +38,67 @@ mod test {
         let check_proof = proof.prove();
         end_tscope!();
 
+        start_tscope!("Prove with partial_evaluate");
+        let round_polys = prove_with_partial_evaluate(&eval_points, Fq::from(1 << 10));
+        end_tscope!();
+        assert_eq!(round_polys, check_proof.round_polys);
+
         start_tscope!("Verify");
         let mut verify = Verify::new(&eval_points);
-        assert_eq!(verify.verify(check_proof), true);
+        assert!(verify.verify(check_proof).is_ok());
         end_tscope!();
 
         print_summary!();
+
+        // folding in place takes about five operations per table entry, rebuilding the
+        // hypercube every round (mostly its coordinates) several times as many
+        let summary = summary!();
+        let in_place = summary.scope("Prove").total_ops();
+        let with_partial_evaluate = summary.scope("Prove with partial_evaluate").total_ops();
+        assert!(in_place * 2 < with_partial_evaluate);
+    }
+
+    // the prover's rounds as they were before folding in place: a new polynomial every round
+    fn prove_with_partial_evaluate<F: PrimeField>(evals: &[F], claimed_sum: F) -> Vec<[F; 2]> {
+        let mut transcript = Transcript::<Keccak256, F>::init(Keccak256::default(), b"sumcheck");
+        transcript.append_point(b"poly", evals);
+        transcript.append_field(b"claimed_sum", &claimed_sum);
+
+        let mut poly = MultilinearPolynomial::new(evals.to_vec());
+        let mut round_polys = Vec::new();
+        for _ in 0..poly.no_of_variables() {
+            let round_poly = split_and_sum(&poly.coefficients);
+            transcript.append_point(b"round_poly", &round_poly);
+            round_polys.push(round_poly);
+            poly = poly.partial_evaluate(0, transcript.challenge_scalar(b"challenge"));
+        }
+        round_polys
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_sumcheck_sha256() {
+        use crate::transcript::Sha256;
+
+        let eval_points = vec![Fq::from(1); 1 << 4];
+        let mut proof = Prover::with_hasher(&eval_points, Fq::from(1 << 4), Sha256::default());
+        let check_proof = proof.prove();
+
+        let mut verify = Verify::with_hasher(&eval_points, Sha256::default());
+        assert!(verify.verify(check_proof).is_ok());
+    }
+
+    #[cfg(all(feature = "sha2", feature = "blake3"))]
+    #[test]
+    fn test_sumcheck_rejects_other_hash_backend() {
+        use crate::transcript::{Blake3, Sha256};
+
+        // a constant polynomial would verify under any challenges
+        let eval_points: Vec<Fq> = (0..1u64 << 4).map(Fq::from).collect();
+        let mut proof = Prover::with_hasher(&eval_points, Fq::from(120), Sha256::default());
+        let check_proof = proof.prove();
+
+        let mut verify = Verify::with_hasher(&eval_points, Blake3::new());
+        assert!(verify.verify(check_proof).is_err());
     }
 }
@@ -0,0 +1,324 @@
+use crate::transcript::HashTrait;
+use ark_ff::{BigInteger, PrimeField};
+
+// Poseidon (HADES design, x^5 S-box) with round constants and MDS matrix generated by the
+// Grain LFSR from the reference implementation, so the parameters for BN254 match the
+// published poseidonperm_x5_254_* instances.
+//
+// The MDS matrix is the first Cauchy matrix the LFSR produces; the reference script's extra
+// invariant-subspace checks are not run, which is fine for the standard BN254 widths (they
+// pass on the first candidate) but should be checked before using other parameter sets.
+
+#[derive(Debug, Clone)]
+pub struct PoseidonParams<F: PrimeField> {
+    pub width: usize,
+    pub full_rounds: usize,
+    pub partial_rounds: usize,
+    pub round_constants: Vec<F>,
+    pub mds: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> PoseidonParams<F> {
+    pub fn generate(width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        let field_bits = F::MODULUS_BIT_SIZE as usize;
+        let mut grain = Grain::new(field_bits, width, full_rounds, partial_rounds);
+
+        let round_constants = (0..(full_rounds + partial_rounds) * width)
+            .map(|_| loop {
+                // rejection sample until the integer is below the modulus
+                if let Some(constant) = F::from_bigint(grain.next_bigint::<F>(field_bits)) {
+                    break constant;
+                }
+            })
+            .collect();
+
+        let mds = loop {
+            let values: Vec<F> = (0..2 * width)
+                .map(|_| {
+                    let value = grain.next_bigint::<F>(field_bits);
+                    F::from_be_bytes_mod_order(&value.to_bytes_be())
+                })
+                .collect();
+            if let Some(mds) = cauchy_matrix(&values[..width], &values[width..]) {
+                break mds;
+            }
+        };
+
+        Self {
+            width,
+            full_rounds,
+            partial_rounds,
+            round_constants,
+            mds,
+        }
+    }
+
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(
+            state.len(),
+            self.width,
+            "state must match the permutation width"
+        );
+        let half_full_rounds = self.full_rounds / 2;
+
+        for round in 0..self.full_rounds + self.partial_rounds {
+            for (i, element) in state.iter_mut().enumerate() {
+                *element += self.round_constants[round * self.width + i];
+            }
+
+            let is_full_round =
+                round < half_full_rounds || round >= half_full_rounds + self.partial_rounds;
+            if is_full_round {
+                state
+                    .iter_mut()
+                    .for_each(|element| *element = sbox(*element));
+            } else {
+                state[0] = sbox(state[0]);
+            }
+
+            let mixed: Vec<F> = self
+                .mds
+                .iter()
+                .map(|row| row.iter().zip(state.iter()).map(|(m, s)| *m * s).sum())
+                .collect();
+            state.copy_from_slice(&mixed);
+        }
+    }
+}
+
+fn sbox<F: PrimeField>(x: F) -> F {
+    let x2 = x.square();
+    x2.square() * x
+}
+
+fn cauchy_matrix<F: PrimeField>(xs: &[F], ys: &[F]) -> Option<Vec<Vec<F>>> {
+    let mut all = xs.to_vec();
+    all.extend_from_slice(ys);
+    for i in 0..all.len() {
+        if all[i + 1..].contains(&all[i]) {
+            return None;
+        }
+    }
+
+    xs.iter()
+        .map(|x| ys.iter().map(|y| (*x + y).inverse()).collect())
+        .collect()
+}
+
+// Grain LFSR in self-shrinking mode, as specified for Poseidon parameter generation
+struct Grain {
+    bits: Vec<bool>,
+}
+
+impl Grain {
+    fn new(field_bits: usize, width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        let mut bits = Vec::with_capacity(80);
+        // field type: 1 = prime field, S-box type: 0 = x^alpha
+        push_bits(&mut bits, 1, 2);
+        push_bits(&mut bits, 0, 4);
+        push_bits(&mut bits, field_bits, 12);
+        push_bits(&mut bits, width, 12);
+        push_bits(&mut bits, full_rounds, 10);
+        push_bits(&mut bits, partial_rounds, 10);
+        bits.extend(std::iter::repeat_n(true, 30));
+
+        let mut grain = Self { bits };
+        for _ in 0..160 {
+            grain.step();
+        }
+        grain
+    }
+
+    fn step(&mut self) -> bool {
+        let b = &self.bits;
+        let new_bit = b[62] ^ b[51] ^ b[38] ^ b[23] ^ b[13] ^ b[0];
+        self.bits.remove(0);
+        self.bits.push(new_bit);
+        new_bit
+    }
+
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.step();
+            let bit = self.step();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    fn next_bigint<F: PrimeField>(&mut self, no_of_bits: usize) -> F::BigInt {
+        let bits: Vec<bool> = (0..no_of_bits).map(|_| self.next_bit()).collect();
+        F::BigInt::from_bits_be(&bits)
+    }
+}
+
+fn push_bits(bits: &mut Vec<bool>, value: usize, width: usize) {
+    for i in (0..width).rev() {
+        bits.push((value >> i) & 1 == 1);
+    }
+}
+
+// Sponge over the Poseidon permutation exposed through HashTrait. Bytes are packed 31 at a
+// time into field elements; state[0] is the capacity element and the rest is the rate.
+#[derive(Debug, Clone)]
+pub struct PoseidonHasher<F: PrimeField> {
+    params: PoseidonParams<F>,
+    state: Vec<F>,
+    position: usize,
+    pending: Vec<u8>,
+}
+
+const BYTES_PER_ELEMENT: usize = 31;
+
+impl<F: PrimeField> PoseidonHasher<F> {
+    pub fn new(params: PoseidonParams<F>) -> Self {
+        let state = vec![F::zero(); params.width];
+        Self {
+            params,
+            state,
+            position: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    fn absorb_element(params: &PoseidonParams<F>, state: &mut [F], position: &mut usize, x: F) {
+        state[1 + *position] += x;
+        *position += 1;
+        if *position == params.width - 1 {
+            params.permute(state);
+            *position = 0;
+        }
+    }
+}
+
+// width 3 (rate 2) instance over the BN254 scalar field: R_F = 8, R_P = 57
+impl Default for PoseidonHasher<ark_bn254::Fr> {
+    fn default() -> Self {
+        Self::new(PoseidonParams::generate(3, 8, 57))
+    }
+}
+
+impl<F: PrimeField> HashTrait for PoseidonHasher<F> {
+    const NAME: &'static str = "poseidon";
+
+    fn append(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+        while self.pending.len() >= BYTES_PER_ELEMENT {
+            let chunk: Vec<u8> = self.pending.drain(..BYTES_PER_ELEMENT).collect();
+            let element = F::from_le_bytes_mod_order(&chunk);
+            Self::absorb_element(&self.params, &mut self.state, &mut self.position, element);
+        }
+    }
+
+    fn generate_hash(&self) -> Vec<u8> {
+        let mut state = self.state.clone();
+        let mut position = self.position;
+
+        // pad the trailing bytes with a 0x01 marker so inputs of different lengths differ
+        let mut last_chunk = self.pending.clone();
+        last_chunk.push(1);
+        let element = F::from_le_bytes_mod_order(&last_chunk);
+        Self::absorb_element(&self.params, &mut state, &mut position, element);
+        if position != 0 {
+            self.params.permute(&mut state);
+        }
+
+        state[1].into_bigint().to_bytes_le()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::Transcript;
+    use ark_bn254::{Fq, Fr};
+
+    fn from_hex(hex: &str) -> Fr {
+        let hex = hex.trim_start_matches("0x");
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        Fr::from_be_bytes_mod_order(&bytes)
+    }
+
+    #[test]
+    fn test_grain_parameters() {
+        let params = PoseidonParams::<Fr>::generate(3, 8, 57);
+        assert_eq!(params.round_constants.len(), 65 * 3);
+        assert_eq!(
+            params.round_constants[0],
+            from_hex("0x0ee9a592ba9a9518d05986d656f40c2114c4993c11bb29938d21d47304cd8e6e")
+        );
+        assert_eq!(
+            params.mds[0][0],
+            from_hex("0x109b7f411ba0e4c9b2b70caf5c36a7b194be7c11ad24378bfedb68592ba8118b")
+        );
+    }
+
+    // known-answer tests from the Poseidon reference implementation (poseidonperm_x5_254_*)
+    #[test]
+    fn test_permutation_x5_254_3() {
+        let params = PoseidonParams::<Fr>::generate(3, 8, 57);
+        let mut state = vec![Fr::from(0), Fr::from(1), Fr::from(2)];
+        params.permute(&mut state);
+        assert_eq!(
+            state,
+            vec![
+                from_hex("0x115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a"),
+                from_hex("0x0fca49b798923ab0239de1c9e7a4a9a2210312b6a2f616d18b5a87f9b628ae29"),
+                from_hex("0x0e7ae82e40091e63cbd4f16a6d16310b3729d4b6e138fcf54110e2867045a30c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_permutation_x5_254_5() {
+        let params = PoseidonParams::<Fr>::generate(5, 8, 60);
+        let mut state: Vec<Fr> = (0..5u64).map(Fr::from).collect();
+        params.permute(&mut state);
+        assert_eq!(
+            state,
+            vec![
+                from_hex("0x299c867db6c1fdd79dcefa40e4510b9837e60ebb1ce0663dbaa525df65250465"),
+                from_hex("0x1148aaef609aa338b27dafd89bb98862d8bb2b429aceac47d86206154ffe053d"),
+                from_hex("0x24febb87fed7462e23f6665ff9a0111f4044c38ee1672c1ac6b0637d34f24907"),
+                from_hex("0x0eb08f6d809668a981c186beaf6110060707059576406b248e5d9cf6e78b3d3e"),
+                from_hex("0x07748bc6877c9b82c8b98666ee9d0626ec7f5be4205f79ee8528ef1c4a376fc7"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_poseidon_hasher() {
+        let mut hasher = PoseidonHasher::<Fr>::default();
+        hasher.append(b"hello world");
+        let first = hasher.generate_hash();
+        assert_eq!(first, hasher.generate_hash());
+
+        let mut other = PoseidonHasher::<Fr>::default();
+        other.append(b"hello world!");
+        assert_ne!(first, other.generate_hash());
+
+        // appending in pieces is the same as appending at once
+        let mut pieces = PoseidonHasher::<Fr>::default();
+        pieces.append(b"hello ");
+        pieces.append(b"world");
+        assert_eq!(first, pieces.generate_hash());
+    }
+
+    #[test]
+    fn test_poseidon_transcript() {
+        let mut prover =
+            Transcript::<PoseidonHasher<Fr>, Fq>::init(PoseidonHasher::default(), b"test");
+        let mut verifier =
+            Transcript::<PoseidonHasher<Fr>, Fq>::init(PoseidonHasher::default(), b"test");
+        prover.append_field(b"value", &Fq::from(7));
+        verifier.append_field(b"value", &Fq::from(7));
+
+        let challenges = prover.challenge_scalars(b"challenge", 2);
+        assert_ne!(challenges[0], challenges[1]);
+        assert_eq!(challenges, verifier.challenge_scalars(b"challenge", 2));
+    }
+}
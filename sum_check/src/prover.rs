@@ -1,5 +1,5 @@
-use crate::transcript::Transcript;
-use ark_ff::{BigInteger, PrimeField};
+use crate::transcript::{HashTrait, Transcript};
+use ark_ff::{Field, PrimeField};
 use multivariate_poly::MultilinearPolynomial;
 use sha3::Keccak256;
 
@@ -9,53 +9,101 @@ pub struct Proof<F: PrimeField> {
     pub round_polys: Vec<[F; 2]>,
 }
 
+// Same proof with every round message reduced to g(0). g(1) is left out because it must
+// equal the running claim minus g(0), so the verifier rebuilds it while checking the rounds.
 #[derive(Debug, Clone)]
-pub struct Prover<F: PrimeField> {
+pub struct CompressedProof<F: PrimeField> {
+    pub claimed_sum: F,
+    pub round_polys: Vec<F>,
+}
+
+impl<F: PrimeField> Proof<F> {
+    pub fn compress(&self) -> CompressedProof<F> {
+        CompressedProof {
+            claimed_sum: self.claimed_sum,
+            round_polys: self
+                .round_polys
+                .iter()
+                .map(|round_poly| round_poly[0])
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Prover<F: PrimeField, K: HashTrait = Keccak256> {
     pub initial_poly: MultilinearPolynomial<F>,
     pub claimed_sum: F,
-    pub transcripts: Transcript<Keccak256, F>,
+    pub transcripts: Transcript<K, F>,
 }
 
 impl<F: PrimeField> Prover<F> {
     pub fn new(poly_eval_points: &Vec<F>, claimed_sum: F) -> Self {
-        let poly = MultilinearPolynomial::new(poly_eval_points.clone());
+        Self::with_hasher(poly_eval_points, claimed_sum, Keccak256::default())
+    }
+}
+
+impl<F: PrimeField, K: HashTrait> Prover<F, K> {
+    pub fn with_hasher(poly_eval_points: &[F], claimed_sum: F, hash_function: K) -> Self {
+        let poly = MultilinearPolynomial::new(poly_eval_points.to_vec());
         Self {
             initial_poly: poly,
-            claimed_sum: claimed_sum,
-            transcripts: Transcript::init(Keccak256::default()),
+            claimed_sum,
+            transcripts: Transcript::init(hash_function, b"sumcheck"),
         }
     }
 
     pub fn prove(&mut self) -> Proof<F> {
-        let mut round_polys = Vec::new();
-
         // append poly eval coefficients
         self.transcripts
-            .absorb(self.initial_poly.convert_to_bytes().as_slice());
-        self.transcripts
-            .absorb(self.claimed_sum.into_bigint().to_bytes_be().as_slice());
+            .append_point(b"poly", &self.initial_poly.coefficients);
 
-        let mut poly = self.initial_poly.clone();
-
-        for _ in 0..self.initial_poly.no_of_variables() {
-            let round_poly_coeffs = split_and_sum(&poly.coefficients);
-            let round_poly = MultilinearPolynomial::new(round_poly_coeffs.to_vec());
-            self.transcripts
-                .absorb(round_poly.convert_to_bytes().as_slice());
-            round_polys.push(round_poly_coeffs);
+        prove(&self.initial_poly, self.claimed_sum, &mut self.transcripts)
+    }
+}
 
-            let challenge: F = self.transcripts.squeeze();
-            poly = poly.partial_evaluate(0, challenge);
-        }
+// Runs the sumcheck rounds on a transcript the caller has already bound the polynomial to
+// (e.g. by appending a commitment to it), so it can be checked with verifier::verify.
+pub fn prove<F: PrimeField, K: HashTrait>(
+    poly: &MultilinearPolynomial<F>,
+    claimed_sum: F,
+    transcript: &mut Transcript<K, F>,
+) -> Proof<F> {
+    let (proof, _) = prove_rounds(poly.coefficients.clone(), claimed_sum, transcript);
+    proof
+}
 
-        Proof {
-            claimed_sum: self.claimed_sum,
-            round_polys: round_polys,
-        }
+// the rounds of prove on an evaluation table it can fold in place; also hands back the
+// challenges for callers that need to open other polynomials at the same point
+pub(crate) fn prove_rounds<F: PrimeField, K: HashTrait>(
+    mut table: Vec<F>,
+    claimed_sum: F,
+    transcript: &mut Transcript<K, F>,
+) -> (Proof<F>, Vec<F>) {
+    transcript.append_field(b"claimed_sum", &claimed_sum);
+
+    let no_of_variables = table.len().trailing_zeros() as usize;
+    let mut round_polys = Vec::with_capacity(no_of_variables);
+    let mut challenges = Vec::with_capacity(no_of_variables);
+
+    for _ in 0..no_of_variables {
+        let round_poly_coeffs = split_and_sum(&table);
+        transcript.append_point(b"round_poly", &round_poly_coeffs);
+        round_polys.push(round_poly_coeffs);
+
+        let challenge: F = transcript.challenge_scalar(b"challenge");
+        fold_in_place(&mut table, challenge);
+        challenges.push(challenge);
     }
+
+    let proof = Proof {
+        claimed_sum,
+        round_polys,
+    };
+    (proof, challenges)
 }
 
-pub(crate) fn split_and_sum<F: PrimeField>(poly_coeff: &Vec<F>) -> [F; 2] {
+pub(crate) fn split_and_sum<F: Field>(poly_coeff: &Vec<F>) -> [F; 2] {
     let mut result = [F::zero(); 2];
     let mid = poly_coeff.len() / 2;
     let (left, right) = poly_coeff.split_at(mid);
@@ -69,6 +117,18 @@ pub(crate) fn split_and_sum<F: PrimeField>(poly_coeff: &Vec<F>) -> [F; 2] {
     result
 }
 
+// Fixes the first variable to r: the same as partial_evaluate(0, r), but without building
+// the hypercube. The lower half of the table is overwritten and the upper half dropped
+// (truncate keeps the capacity, so nothing is reallocated).
+pub fn fold_in_place<F: Field>(table: &mut Vec<F>, r: F) {
+    let half = table.len() / 2;
+    let (low, high) = table.split_at_mut(half);
+    for (low, high) in low.iter_mut().zip(high.iter()) {
+        *low += r * (*high - *low);
+    }
+    table.truncate(half);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -86,4 +146,16 @@ mod tests {
         // assert_eq!(result, [Fq::from((1 << 20) / 2), Fq::from((1 << 20) / 2)]);
         // print_summary!();
     }
+
+    #[test]
+    fn test_fold_in_place() {
+        let poly = MultilinearPolynomial::new(
+            [0, 0, 0, 3, 0, 0, 2, 5].into_iter().map(Fq::from).collect(),
+        );
+        let r = Fq::from(7);
+
+        let mut table = poly.coefficients.clone();
+        fold_in_place(&mut table, r);
+        assert_eq!(table, poly.partial_evaluate(0, r).coefficients);
+    }
 }
@@ -0,0 +1,183 @@
+use crate::{
+    prover::{fold_in_place, split_and_sum, Proof},
+    transcript::{HashTrait, Transcript},
+};
+use ark_ff::PrimeField;
+use multivariate_poly::eq_polynomial;
+use sha3::Keccak256;
+
+// Small-memory sumcheck prover for evaluation tables that are only available as a stream.
+// The n rounds are split into `no_of_passes` batches of about n/k variables. Each pass reads
+// the whole table once and collapses it into a table over just the batch's variables:
+//   T(y) = Σ_z Σ_s eq(z, r) * p(z, y, s)
+// where z are the variables already bound to the challenges r of earlier passes and s the
+// variables of later passes. The batch's rounds then run on T exactly like the in-memory
+// prover, so memory stays at O(k * 2^(n/k)): T plus one eq table per finished pass.
+// One extra pass at the start binds the table to the transcript, and since every round
+// polynomial is computed over the same values, proofs match prover::Prover byte for byte.
+
+pub trait EvaluationStream<F: PrimeField> {
+    fn no_of_variables(&self) -> usize;
+
+    // a fresh pass over the 2^n evaluations, in the same order as MultilinearPolynomial
+    fn evaluations(&self) -> impl Iterator<Item = F> + '_;
+}
+
+impl<F: PrimeField> EvaluationStream<F> for Vec<F> {
+    fn no_of_variables(&self) -> usize {
+        self.len().trailing_zeros() as usize
+    }
+
+    fn evaluations(&self) -> impl Iterator<Item = F> + '_ {
+        self.iter().copied()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamingProver<F: PrimeField, S: EvaluationStream<F>, K: HashTrait = Keccak256> {
+    pub stream: S,
+    pub claimed_sum: F,
+    pub no_of_passes: usize,
+    pub transcript: Transcript<K, F>,
+}
+
+impl<F: PrimeField, S: EvaluationStream<F>> StreamingProver<F, S> {
+    pub fn new(stream: S, claimed_sum: F, no_of_passes: usize) -> Self {
+        Self::with_hasher(stream, claimed_sum, no_of_passes, Keccak256::default())
+    }
+}
+
+impl<F: PrimeField, S: EvaluationStream<F>, K: HashTrait> StreamingProver<F, S, K> {
+    pub fn with_hasher(stream: S, claimed_sum: F, no_of_passes: usize, hash_function: K) -> Self {
+        assert!(no_of_passes > 0, "the prover needs at least one pass");
+        Self {
+            stream,
+            claimed_sum,
+            no_of_passes,
+            transcript: Transcript::init(hash_function, b"sumcheck"),
+        }
+    }
+
+    pub fn prove(&mut self) -> Proof<F> {
+        let no_of_variables = self.stream.no_of_variables();
+        self.transcript.append_point_stream(
+            b"poly",
+            1 << no_of_variables,
+            self.stream.evaluations(),
+        );
+        self.transcript
+            .append_field(b"claimed_sum", &self.claimed_sum);
+
+        let batch_size = no_of_variables.div_ceil(self.no_of_passes).max(1);
+        let mut round_polys = Vec::with_capacity(no_of_variables);
+        // (number of variables, eq table over its challenges) for every finished pass
+        let mut eq_tables: Vec<(usize, Vec<F>)> = Vec::new();
+        let mut round = 0;
+
+        while round < no_of_variables {
+            let batch_variables = batch_size.min(no_of_variables - round);
+            let later_variables = no_of_variables - round - batch_variables;
+            let batch_mask = (1 << batch_variables) - 1;
+
+            let mut table = vec![F::zero(); 1 << batch_variables];
+            let mut current_prefix = None;
+            let mut weight = F::one();
+            for (i, eval) in self.stream.evaluations().enumerate() {
+                // the bound prefix only changes every 2^(n - round) evaluations
+                let prefix = i >> (no_of_variables - round);
+                if current_prefix != Some(prefix) {
+                    weight = prefix_weight(&eq_tables, prefix);
+                    current_prefix = Some(prefix);
+                }
+                table[(i >> later_variables) & batch_mask] += weight * eval;
+            }
+
+            let mut challenges = Vec::with_capacity(batch_variables);
+            for _ in 0..batch_variables {
+                let round_poly_coeffs = split_and_sum(&table);
+                self.transcript
+                    .append_point(b"round_poly", &round_poly_coeffs);
+                round_polys.push(round_poly_coeffs);
+
+                let challenge: F = self.transcript.challenge_scalar(b"challenge");
+                fold_in_place(&mut table, challenge);
+                challenges.push(challenge);
+            }
+
+            eq_tables.push((batch_variables, eq_polynomial(&challenges).coefficients));
+            round += batch_variables;
+        }
+
+        Proof {
+            claimed_sum: self.claimed_sum,
+            round_polys,
+        }
+    }
+}
+
+// eq(z, r) for the bound prefix z, as the product of the per-pass eq tables
+fn prefix_weight<F: PrimeField>(eq_tables: &[(usize, Vec<F>)], prefix: usize) -> F {
+    let mut prefix = prefix;
+    let mut weight = F::one();
+    for (no_of_variables, table) in eq_tables.iter().rev() {
+        weight *= table[prefix & ((1 << no_of_variables) - 1)];
+        prefix >>= no_of_variables;
+    }
+    weight
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prover::Prover, verifier::Verify};
+    use ark_bn254::Fq;
+    use ark_ff::BigInteger;
+
+    // evaluations computed on the fly, never held in memory
+    struct Generated {
+        no_of_variables: usize,
+    }
+
+    impl EvaluationStream<Fq> for Generated {
+        fn no_of_variables(&self) -> usize {
+            self.no_of_variables
+        }
+
+        fn evaluations(&self) -> impl Iterator<Item = Fq> + '_ {
+            (0..1u64 << self.no_of_variables).map(|i| Fq::from(i * i + 3))
+        }
+    }
+
+    fn proof_bytes(proof: &Proof<Fq>) -> Vec<u8> {
+        let mut bytes = proof.claimed_sum.into_bigint().to_bytes_be();
+        for round_poly in &proof.round_polys {
+            for eval in round_poly {
+                bytes.extend(eval.into_bigint().to_bytes_be());
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_streaming_prover_matches_prover() {
+        let stream = Generated { no_of_variables: 7 };
+        let evals: Vec<Fq> = stream.evaluations().collect();
+        let claimed_sum: Fq = evals.iter().sum();
+        let expected = Prover::new(&evals, claimed_sum).prove();
+
+        for no_of_passes in [1, 2, 3, 7, 10] {
+            let proof =
+                StreamingProver::new(Generated { no_of_variables: 7 }, claimed_sum, no_of_passes)
+                    .prove();
+            assert_eq!(proof_bytes(&proof), proof_bytes(&expected));
+            assert!(Verify::new(&evals).verify(proof).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_streaming_prover_over_vec() {
+        let evals: Vec<Fq> = [0, 0, 0, 3, 0, 0, 2, 5].into_iter().map(Fq::from).collect();
+        let proof = StreamingProver::new(evals.clone(), Fq::from(10), 2).prove();
+        assert!(Verify::new(&evals).verify(proof).is_ok());
+    }
+}
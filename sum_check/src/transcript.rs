@@ -1,11 +1,22 @@
-// use ark_bn254::Fq;
-// use univariate_poly::Polynomial;
-// use multivariate_poly::MultilinearPolynomial;
-
-use ark_ff::PrimeField;
+use crate::error::VerificationError;
+use ark_ff::{BigInteger, Field, PrimeField};
 use sha3::{Digest, Keccak256};
 use std::marker::PhantomData;
 
+pub mod recording;
+
+#[cfg(feature = "blake2")]
+pub use blake2::Blake2s256;
+#[cfg(feature = "blake3")]
+pub use blake3::Hasher as Blake3;
+#[cfg(feature = "sha2")]
+pub use sha2::Sha256;
+// sha3 is always a dependency, so Sha3_256 needs no feature of its own
+pub use sha3::Sha3_256;
+
+// Fiat-Shamir transcript in the style of Merlin: every message is absorbed together with a
+// label and its length, the transcript starts from a protocol domain separator, and every
+// challenge is fed back into the state so consecutive challenges are independent.
 #[derive(Debug, Clone)]
 pub struct Transcript<K: HashTrait, F: PrimeField> {
     _field: PhantomData<F>,
@@ -13,35 +24,214 @@ pub struct Transcript<K: HashTrait, F: PrimeField> {
 }
 
 impl<K: HashTrait, F: PrimeField> Transcript<K, F> {
-    pub fn init(hash_function: K) -> Self {
-        Self {
+    pub fn init(hash_function: K, domain_separator: &[u8]) -> Self {
+        let mut transcript = Self {
             _field: PhantomData,
             hash_function,
-        }
+        };
+        // the hash backend is part of the domain, so proofs can't be checked under another one
+        let separator = [K::NAME.as_bytes(), b":", domain_separator].concat();
+        transcript.append_message(b"dom-sep", &separator);
+        transcript
     }
 
-    pub fn absorb(&mut self, data: &[u8]) {
-        self.hash_function.append(data);
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        self.absorb(label);
+        self.absorb(message);
+        self.hash_function
+            .record(EntryKind::Absorb, label, message.len());
+    }
+
+    pub fn append_field(&mut self, label: &[u8], element: &F) {
+        self.append_message(label, &element.into_bigint().to_bytes_be());
+    }
+
+    pub fn append_point(&mut self, label: &[u8], point: &[F]) {
+        let bytes: Vec<u8> = point
+            .iter()
+            .flat_map(|element| element.into_bigint().to_bytes_be())
+            .collect();
+        self.append_message(label, &bytes);
     }
 
-    pub fn squeeze(&self) -> F {
+    // same bytes as append_point, but the elements are hashed as they come instead of being
+    // collected first; `len` is the number of elements the iterator yields
+    pub fn append_point_stream(
+        &mut self,
+        label: &[u8],
+        len: usize,
+        point: impl IntoIterator<Item = F>,
+    ) {
+        let element_size = F::zero().into_bigint().to_bytes_be().len();
+        self.absorb(label);
+        self.hash_function
+            .append(&((len * element_size) as u64).to_le_bytes());
+
+        let mut count = 0;
+        for element in point {
+            self.hash_function
+                .append(&element.into_bigint().to_bytes_be());
+            count += 1;
+        }
+        assert_eq!(
+            count, len,
+            "stream length doesn't match the announced length"
+        );
+        self.hash_function
+            .record(EntryKind::Absorb, label, len * element_size);
+    }
+
+    pub fn challenge_scalar(&mut self, label: &[u8]) -> F {
+        self.absorb(label);
         let hash_output = self.hash_function.generate_hash();
+        self.hash_function
+            .record(EntryKind::Squeeze, label, hash_output.len());
+        // ratchet: the next challenge depends on this one
+        self.hash_function.append(&hash_output);
         F::from_le_bytes_mod_order(&hash_output)
     }
+
+    pub fn challenge_scalars(&mut self, label: &[u8], n: usize) -> Vec<F> {
+        (0..n).map(|_| self.challenge_scalar(label)).collect()
+    }
+
+    // elements of an extension of F, absorbed as their coordinates over F
+    pub fn append_extension<E: Field<BasePrimeField = F>>(&mut self, label: &[u8], point: &[E]) {
+        let coordinates: Vec<F> = point
+            .iter()
+            .flat_map(|element| element.to_base_prime_field_elements())
+            .collect();
+        self.append_point(label, &coordinates);
+    }
+
+    // one base field challenge per coordinate of the extension
+    pub fn challenge_extension<E: Field<BasePrimeField = F>>(&mut self, label: &[u8]) -> E {
+        let coordinates = self.challenge_scalars(label, E::extension_degree() as usize);
+        E::from_base_prime_field_elems(coordinates)
+            .expect("extension degree matches the number of coordinates")
+    }
+
+    // length-prefixed so that message boundaries can't be shifted
+    fn absorb(&mut self, data: &[u8]) {
+        self.hash_function
+            .append(&(data.len() as u64).to_le_bytes());
+        self.hash_function.append(data);
+    }
+}
+
+// Proof-of-work grinding: before squeezing challenges the prover searches for a nonce that,
+// hashed together with the current state, gives `bits` leading zero bits. Every challenge drawn
+// afterwards then costs a cheating prover about 2^bits extra hashes to resample, so the
+// protocol gains `bits` bits of soundness for a single u64 in the proof. Past
+// MAX_GRINDING_BITS the search takes too long to be of any use, so both sides refuse it.
+pub const MAX_GRINDING_BITS: u32 = 32;
+
+impl<K: HashTrait + Clone, F: PrimeField> Transcript<K, F> {
+    pub fn grind(&mut self, label: &[u8], bits: u32) -> u64 {
+        self.absorb_grinding_params(label, bits);
+        // 2^64 nonces against 2^bits expected hashes, running out is practically impossible
+        let nonce = (0..=u64::MAX)
+            .find(|nonce| self.has_proof_of_work(*nonce, bits))
+            .expect("no u64 nonce has the proof of work");
+        self.append_message(b"nonce", &nonce.to_le_bytes());
+        nonce
+    }
+
+    // one hash, against the 2^bits expected for grind
+    pub fn verify_grinding(
+        &mut self,
+        label: &[u8],
+        bits: u32,
+        nonce: u64,
+    ) -> Result<(), VerificationError<F>> {
+        self.absorb_grinding_params(label, bits);
+        if !self.has_proof_of_work(nonce, bits) {
+            return Err(VerificationError::InsufficientProofOfWork { bits });
+        }
+        self.append_message(b"nonce", &nonce.to_le_bytes());
+        Ok(())
+    }
+
+    fn absorb_grinding_params(&mut self, label: &[u8], bits: u32) {
+        assert!(
+            bits <= MAX_GRINDING_BITS,
+            "can't grind {bits} bits, at most {MAX_GRINDING_BITS} are supported"
+        );
+        self.append_message(label, &bits.to_le_bytes());
+    }
+
+    fn has_proof_of_work(&self, nonce: u64, bits: u32) -> bool {
+        let mut state = self.hash_function.clone();
+        state.append(&nonce.to_le_bytes());
+        leading_zero_bits(&state.generate_hash()) >= bits
+    }
+}
+
+fn leading_zero_bits(bytes: &[u8]) -> u32 {
+    let mut count = 0;
+    for byte in bytes {
+        count += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    count
 }
 
 pub trait HashTrait {
+    // identifies the backend in the transcript's domain separator
+    const NAME: &'static str;
+
     fn append(&mut self, data: &[u8]);
     fn generate_hash(&self) -> Vec<u8>;
+
+    // called by the transcript after every absorb and squeeze; only recording::Recorder
+    // keeps track of them
+    fn record(&mut self, _kind: EntryKind, _label: &[u8], _len: usize) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Absorb,
+    Squeeze,
+}
+
+macro_rules! impl_hash_trait_for_digest {
+    ($hasher:ty, $name:literal) => {
+        impl HashTrait for $hasher {
+            const NAME: &'static str = $name;
+
+            fn append(&mut self, data: &[u8]) {
+                self.update(data);
+            }
+
+            fn generate_hash(&self) -> Vec<u8> {
+                self.clone().finalize().to_vec()
+            }
+        }
+    };
 }
 
-impl HashTrait for Keccak256 {
+impl_hash_trait_for_digest!(Keccak256, "keccak256");
+
+impl_hash_trait_for_digest!(Sha3_256, "sha3-256");
+
+#[cfg(feature = "sha2")]
+impl_hash_trait_for_digest!(Sha256, "sha256");
+
+#[cfg(feature = "blake2")]
+impl_hash_trait_for_digest!(Blake2s256, "blake2s256");
+
+#[cfg(feature = "blake3")]
+impl HashTrait for Blake3 {
+    const NAME: &'static str = "blake3";
+
     fn append(&mut self, data: &[u8]) {
         self.update(data);
     }
 
     fn generate_hash(&self) -> Vec<u8> {
-        self.clone().finalize().to_vec()
+        self.finalize().as_bytes().to_vec()
     }
 }
 
@@ -52,9 +242,155 @@ mod tests {
 
     #[test]
     fn test_transcript() {
-        let mut transcript = Transcript::<Keccak256, Fq>::init(Keccak256::new());
-        transcript.absorb(b"hello world");
-        let challenge: Fq = transcript.squeeze();
+        let mut transcript = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        transcript.append_message(b"greeting", b"hello world");
+        let challenge: Fq = transcript.challenge_scalar(b"challenge");
         dbg!("{}", challenge);
     }
+
+    #[test]
+    fn test_consecutive_challenges_differ() {
+        let mut transcript = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        transcript.append_field(b"value", &Fq::from(7));
+        let first = transcript.challenge_scalar(b"challenge");
+        let second = transcript.challenge_scalar(b"challenge");
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_transcripts_are_deterministic() {
+        let mut prover = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        let mut verifier = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        prover.append_point(b"point", &[Fq::from(1), Fq::from(2)]);
+        verifier.append_point(b"point", &[Fq::from(1), Fq::from(2)]);
+        assert_eq!(
+            prover.challenge_scalars(b"challenge", 3),
+            verifier.challenge_scalars(b"challenge", 3)
+        );
+    }
+
+    #[test]
+    fn test_append_point_stream() {
+        let point: Vec<Fq> = (0..5u64).map(Fq::from).collect();
+        let mut collected = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        let mut streamed = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        collected.append_point(b"point", &point);
+        streamed.append_point_stream(b"point", point.len(), point.iter().copied());
+        assert_eq!(
+            collected.challenge_scalar(b"challenge"),
+            streamed.challenge_scalar(b"challenge")
+        );
+    }
+
+    #[test]
+    fn test_grinding() {
+        let mut prover = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        let mut verifier = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        prover.append_field(b"value", &Fq::from(7));
+        verifier.append_field(b"value", &Fq::from(7));
+
+        let nonce = prover.grind(b"pow", 8);
+        let mut rejecting = verifier.clone();
+        assert_eq!(verifier.verify_grinding(b"pow", 8, nonce), Ok(()));
+        assert_eq!(
+            prover.challenge_scalar(b"challenge"),
+            verifier.challenge_scalar(b"challenge")
+        );
+
+        // the smallest valid nonce is returned, so every smaller one fails
+        if nonce > 0 {
+            assert_eq!(
+                rejecting.verify_grinding(b"pow", 8, nonce - 1),
+                Err(VerificationError::InsufficientProofOfWork { bits: 8 })
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "can't grind 33 bits, at most 32 are supported")]
+    fn test_grinding_past_the_cap() {
+        let mut transcript = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        transcript.grind(b"pow", MAX_GRINDING_BITS + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't grind 64 bits, at most 32 are supported")]
+    fn test_verify_grinding_past_the_cap() {
+        let mut transcript = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        let _ = transcript.verify_grinding(b"pow", 64, 0);
+    }
+
+    #[test]
+    fn test_leading_zero_bits() {
+        assert_eq!(leading_zero_bits(&[0xff, 0x00]), 0);
+        assert_eq!(leading_zero_bits(&[0x00, 0x10, 0x00]), 11);
+        assert_eq!(leading_zero_bits(&[0x00, 0x00]), 16);
+    }
+
+    #[cfg(all(feature = "sha2", feature = "blake2", feature = "blake3"))]
+    #[test]
+    fn test_hash_backends_give_different_challenges() {
+        fn challenge<K: HashTrait>(hash_function: K) -> Fq {
+            let mut transcript = Transcript::<K, Fq>::init(hash_function, b"test");
+            transcript.append_message(b"message", b"hello world");
+            transcript.challenge_scalar(b"challenge")
+        }
+
+        let challenges = [
+            challenge(Keccak256::new()),
+            challenge(Sha3_256::new()),
+            challenge(Sha256::new()),
+            challenge(Blake2s256::new()),
+            challenge(Blake3::new()),
+        ];
+        for i in 0..challenges.len() {
+            assert!(!challenges[i + 1..].contains(&challenges[i]));
+        }
+    }
+
+    #[test]
+    fn test_hash_backend_is_part_of_the_domain() {
+        // Keccak256 under another name: the same hash function, but a different domain
+        struct Renamed(Keccak256);
+        impl HashTrait for Renamed {
+            const NAME: &'static str = "renamed";
+
+            fn append(&mut self, data: &[u8]) {
+                self.0.append(data);
+            }
+
+            fn generate_hash(&self) -> Vec<u8> {
+                self.0.generate_hash()
+            }
+        }
+
+        let mut keccak = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        let mut renamed = Transcript::<Renamed, Fq>::init(Renamed(Keccak256::new()), b"test");
+        assert_ne!(
+            keccak.challenge_scalar(b"challenge"),
+            renamed.challenge_scalar(b"challenge")
+        );
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        let mut sumcheck = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"sumcheck");
+        let mut gkr = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"gkr");
+        sumcheck.append_message(b"message", b"hello world");
+        gkr.append_message(b"message", b"hello world");
+        assert_ne!(
+            sumcheck.challenge_scalar(b"challenge"),
+            gkr.challenge_scalar(b"challenge")
+        );
+
+        // same bytes split differently across label and message
+        let mut split_1 = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        let mut split_2 = Transcript::<Keccak256, Fq>::init(Keccak256::new(), b"test");
+        split_1.append_message(b"ab", b"c");
+        split_2.append_message(b"a", b"bc");
+        assert_ne!(
+            split_1.challenge_scalar(b"challenge"),
+            split_2.challenge_scalar(b"challenge")
+        );
+    }
 }
@@ -0,0 +1,351 @@
+use super::{EntryKind, HashTrait, Transcript};
+use ark_ff::PrimeField;
+use std::fmt;
+
+// A transcript that logs every absorb and squeeze. Recorder wraps the hash function, so a
+// RecordingTranscript can be passed to any prover or verifier that takes a Transcript<K, F>.
+pub type RecordingTranscript<K, F> = Transcript<Recorder<K>, F>;
+
+impl<K: HashTrait, F: PrimeField> Transcript<Recorder<K>, F> {
+    pub fn log(&self) -> &TranscriptLog {
+        &self.hash_function.log
+    }
+
+    pub fn into_log(self) -> TranscriptLog {
+        self.hash_function.log
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Recorder<K: HashTrait> {
+    hash_function: K,
+    // bytes appended since the last entry
+    pending: Vec<u8>,
+    log: TranscriptLog,
+}
+
+impl<K: HashTrait> Recorder<K> {
+    pub fn new(hash_function: K) -> Self {
+        Self {
+            hash_function,
+            pending: Vec::new(),
+            log: TranscriptLog::default(),
+        }
+    }
+}
+
+impl<K: HashTrait> HashTrait for Recorder<K> {
+    const NAME: &'static str = K::NAME;
+
+    fn append(&mut self, data: &[u8]) {
+        self.hash_function.append(data);
+        self.pending.extend_from_slice(data);
+    }
+
+    fn generate_hash(&self) -> Vec<u8> {
+        self.hash_function.generate_hash()
+    }
+
+    fn record(&mut self, kind: EntryKind, label: &[u8], len: usize) {
+        let output = match kind {
+            EntryKind::Absorb => Vec::new(),
+            EntryKind::Squeeze => self.hash_function.generate_hash(),
+        };
+        self.log.entries.push(TranscriptEntry {
+            kind,
+            label: label.to_vec(),
+            len,
+            bytes: std::mem::take(&mut self.pending),
+            output,
+        });
+    }
+}
+
+// `len` is the size of the message as the transcript saw it, `bytes` everything that was fed
+// to the hash function for this entry (length prefixes and the previous challenge's ratchet
+// included) and `output` the hash a squeeze was drawn from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptEntry {
+    pub kind: EntryKind,
+    pub label: Vec<u8>,
+    pub len: usize,
+    pub bytes: Vec<u8>,
+    pub output: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TranscriptLog {
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl TranscriptLog {
+    // Feeds the recorded bytes to a fresh hash function and checks every squeeze reproduces
+    // its recorded output. Returns the index of the first entry that doesn't.
+    pub fn replay<K: HashTrait>(&self, mut hash_function: K) -> Result<(), usize> {
+        for (index, entry) in self.entries.iter().enumerate() {
+            hash_function.append(&entry.bytes);
+            if entry.kind == EntryKind::Squeeze && hash_function.generate_hash() != entry.output {
+                return Err(index);
+            }
+        }
+        Ok(())
+    }
+
+    // one entry per line: `absorb <label> <len> <bytes>` or `squeeze <label> <len> <bytes>
+    // <output>`, with the label escaped and the byte strings in hex
+    pub fn parse(input: &str) -> Result<Self, ParseLogError> {
+        let mut entries = Vec::new();
+        for (index, line) in input.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let error = |reason| ParseLogError {
+                line: index + 1,
+                reason,
+            };
+
+            let fields: Vec<&str> = line.split(' ').collect();
+            let (kind, field_count) = match fields[0] {
+                "absorb" => (EntryKind::Absorb, 4),
+                "squeeze" => (EntryKind::Squeeze, 5),
+                _ => return Err(error("unknown entry kind")),
+            };
+            if fields.len() != field_count {
+                return Err(error("wrong number of fields"));
+            }
+
+            entries.push(TranscriptEntry {
+                kind,
+                label: unescape(fields[1]).ok_or_else(|| error("invalid label"))?,
+                len: fields[2].parse().map_err(|_| error("invalid length"))?,
+                bytes: from_hex(fields[3]).ok_or_else(|| error("invalid hex"))?,
+                output: match kind {
+                    EntryKind::Absorb => Vec::new(),
+                    EntryKind::Squeeze => {
+                        from_hex(fields[4]).ok_or_else(|| error("invalid hex"))?
+                    }
+                },
+            });
+        }
+        Ok(Self { entries })
+    }
+}
+
+impl fmt::Display for TranscriptLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            let kind = match entry.kind {
+                EntryKind::Absorb => "absorb",
+                EntryKind::Squeeze => "squeeze",
+            };
+            write!(
+                f,
+                "{kind} {} {} {}",
+                escape(&entry.label),
+                entry.len,
+                to_hex(&entry.bytes)
+            )?;
+            if entry.kind == EntryKind::Squeeze {
+                write!(f, " {}", to_hex(&entry.output))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLogError {
+    pub line: usize,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for ParseLogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for ParseLogError {}
+
+// The first entry where a prover and a verifier log disagree. Either side is None when that
+// log ended early.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub index: usize,
+    pub prover: Option<TranscriptEntry>,
+    pub verifier: Option<TranscriptEntry>,
+}
+
+pub fn diff(prover: &TranscriptLog, verifier: &TranscriptLog) -> Option<Divergence> {
+    let len = prover.entries.len().max(verifier.entries.len());
+    (0..len).find_map(|index| {
+        let prover = prover.entries.get(index);
+        let verifier = verifier.entries.get(index);
+        (prover != verifier).then(|| Divergence {
+            index,
+            prover: prover.cloned(),
+            verifier: verifier.cloned(),
+        })
+    })
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn describe(entry: &Option<TranscriptEntry>) -> String {
+            match entry {
+                Some(entry) => {
+                    let kind = match entry.kind {
+                        EntryKind::Absorb => "absorbed",
+                        EntryKind::Squeeze => "squeezed",
+                    };
+                    format!("{kind} \"{}\" ({} bytes)", escape(&entry.label), entry.len)
+                }
+                None => "nothing (log ended)".to_string(),
+            }
+        }
+
+        write!(
+            f,
+            "entry {}: prover {}, verifier {}",
+            self.index,
+            describe(&self.prover),
+            describe(&self.verifier)
+        )?;
+        if let (Some(prover), Some(verifier)) = (&self.prover, &self.verifier) {
+            if prover.kind == verifier.kind
+                && prover.label == verifier.label
+                && prover.len == verifier.len
+            {
+                write!(f, "; same label and length, different bytes")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// printable ASCII is kept as is, everything else (and space and backslash) becomes \xNN
+fn escape(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| match byte {
+            b'!'..=b'~' if *byte != b'\\' => (*byte as char).to_string(),
+            _ => format!("\\x{byte:02x}"),
+        })
+        .collect()
+}
+
+fn unescape(input: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut rest = input.as_bytes();
+    while let Some((first, tail)) = rest.split_first() {
+        if *first == b'\\' {
+            let hex = tail.strip_prefix(b"x")?.get(..2)?;
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &tail[3..];
+        } else {
+            bytes.push(*first);
+            rest = tail;
+        }
+    }
+    Some(bytes)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(input: &str) -> Option<Vec<u8>> {
+    if !input.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..input.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(input.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prover::prove, verifier::verify};
+    use ark_bn254::Fq;
+    use multivariate_poly::MultilinearPolynomial;
+    use sha3::Keccak256;
+
+    fn to_field(input: Vec<u64>) -> Vec<Fq> {
+        input.into_iter().map(Fq::from).collect()
+    }
+
+    fn transcript() -> RecordingTranscript<Keccak256, Fq> {
+        Transcript::init(Recorder::new(Keccak256::default()), b"sumcheck")
+    }
+
+    #[test]
+    fn test_recorded_runs_match() {
+        let poly = MultilinearPolynomial::new(to_field(vec![0, 0, 0, 3, 0, 0, 2, 5]));
+
+        let mut prover_transcript = transcript();
+        let proof = prove(&poly, Fq::from(10), &mut prover_transcript);
+        let mut verifier_transcript = transcript();
+        assert!(verify(&proof, 3, Fq::from(10), &mut verifier_transcript).is_ok());
+
+        let log = prover_transcript.into_log();
+        // dom-sep, claimed_sum and a round_poly and challenge per round
+        assert_eq!(log.entries.len(), 2 + 2 * 3);
+        assert_eq!(diff(&log, verifier_transcript.log()), None);
+        assert_eq!(log.replay(Keccak256::default()), Ok(()));
+
+        let parsed = TranscriptLog::parse(&log.to_string()).unwrap();
+        assert_eq!(parsed, log);
+    }
+
+    #[test]
+    fn test_diff_finds_first_divergence() {
+        let poly = MultilinearPolynomial::new(to_field(vec![0, 0, 0, 3, 0, 0, 2, 5]));
+
+        let mut prover_transcript = transcript();
+        prover_transcript.append_message(b"commitment", b"poly");
+        let proof = prove(&poly, Fq::from(10), &mut prover_transcript);
+
+        // the verifier forgot to absorb the commitment
+        let mut verifier_transcript = transcript();
+        assert!(verify(&proof, 3, Fq::from(10), &mut verifier_transcript).is_err());
+
+        let divergence = diff(prover_transcript.log(), verifier_transcript.log()).unwrap();
+        assert_eq!(divergence.index, 1);
+        assert_eq!(divergence.prover.as_ref().unwrap().label, b"commitment");
+        assert_eq!(divergence.verifier.as_ref().unwrap().label, b"claimed_sum");
+        assert_eq!(
+            divergence.to_string(),
+            "entry 1: prover absorbed \"commitment\" (4 bytes), \
+             verifier absorbed \"claimed_sum\" (32 bytes)"
+        );
+    }
+
+    #[test]
+    fn test_replay_rejects_tampered_log() {
+        let mut transcript = transcript();
+        transcript.append_field(b"value", &Fq::from(7));
+        transcript.challenge_scalar(b"challenge");
+        transcript.append_field(b"value", &Fq::from(8));
+        transcript.challenge_scalar(b"challenge");
+
+        let mut log = transcript.into_log();
+        assert_eq!(log.replay(Keccak256::default()), Ok(()));
+        log.entries[3].bytes[20] ^= 1;
+        assert_eq!(log.replay(Keccak256::default()), Err(4));
+    }
+
+    #[test]
+    fn test_parse_reports_line() {
+        assert_eq!(
+            TranscriptLog::parse("absorb a 1 00\nsqueeze b 32 zz 00\n"),
+            Err(ParseLogError {
+                line: 2,
+                reason: "invalid hex"
+            })
+        );
+        assert_eq!(unescape(&escape(b"a b\\c\n")), Some(b"a b\\c\n".to_vec()));
+    }
+}
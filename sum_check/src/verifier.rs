@@ -1,59 +1,246 @@
-use crate::{prover::Proof, transcript::Transcript};
-use ark_ff::{BigInteger, PrimeField};
+use crate::{
+    error::VerificationError,
+    prover::{CompressedProof, Proof},
+    transcript::{HashTrait, Transcript},
+};
+use ark_ff::{Field, PrimeField};
 use multivariate_poly::MultilinearPolynomial;
 use sha3::Keccak256;
 
 #[derive(Debug, Clone)]
-pub struct Verify<F: PrimeField> {
-    pub transcript: Transcript<Keccak256, F>,
+pub struct Verify<F: PrimeField, K: HashTrait = Keccak256> {
+    pub transcript: Transcript<K, F>,
     pub original_poly: MultilinearPolynomial<F>,
 }
 
 impl<F: PrimeField> Verify<F> {
     pub fn new(coefficients: &Vec<F>) -> Self {
+        Self::with_hasher(coefficients, Keccak256::default())
+    }
+}
+
+impl<F: PrimeField, K: HashTrait> Verify<F, K> {
+    pub fn with_hasher(coefficients: &[F], hash_function: K) -> Self {
         Self {
-            transcript: Transcript::init(Keccak256::default()),
-            original_poly: MultilinearPolynomial::new(coefficients.clone()),
+            transcript: Transcript::init(hash_function, b"sumcheck"),
+            original_poly: MultilinearPolynomial::new(coefficients.to_vec()),
         }
     }
 
-    pub fn verify(&mut self, proof: Proof<F>) -> bool {
-        let mut challenges = vec![];
-
-        // self.initial_poly = initial_poly.clone();
+    pub fn verify(&mut self, proof: Proof<F>) -> Result<(), VerificationError<F>> {
         self.transcript
-            .absorb(self.original_poly.convert_to_bytes().as_slice());
+            .append_point(b"poly", &self.original_poly.coefficients);
+
+        let no_of_variables = self.original_poly.no_of_variables();
+        let sub_claim = verify(
+            &proof,
+            no_of_variables,
+            proof.claimed_sum,
+            &mut self.transcript,
+        )?;
+        self.check_sub_claim(sub_claim)
+    }
+
+    pub fn verify_compressed(
+        &mut self,
+        proof: CompressedProof<F>,
+    ) -> Result<(), VerificationError<F>> {
         self.transcript
-            .absorb(proof.claimed_sum.into_bigint().to_bytes_be().as_slice());
-        let mut claimed_sum = proof.claimed_sum;
-
-        println!("round_polys: {:?}", proof.round_polys);
-
-        for round_poly in proof.round_polys {
-            if claimed_sum != round_poly.iter().sum() {
-                return false;
-            }
-            let converted_poly = MultilinearPolynomial::new(round_poly.to_vec());
-            self.transcript
-                .absorb(converted_poly.convert_to_bytes().as_slice());
-            println!("round poly sum: {:?} ", round_poly.iter().sum::<F>());
-            println!("claimed sum: {}", claimed_sum);
-            let challenge: F = self.transcript.squeeze();
-            claimed_sum = round_poly[0] + challenge * (round_poly[1] - round_poly[0]);
-            challenges.push(challenge);
+            .append_point(b"poly", &self.original_poly.coefficients);
+
+        let no_of_variables = self.original_poly.no_of_variables();
+        let sub_claim = verify_compressed(
+            &proof,
+            no_of_variables,
+            proof.claimed_sum,
+            &mut self.transcript,
+        )?;
+        self.check_sub_claim(sub_claim)
+    }
+
+    fn check_sub_claim(&self, sub_claim: SubClaim<F>) -> Result<(), VerificationError<F>> {
+        let evaluation = self.original_poly.evaluate(&sub_claim.point);
+        if evaluation != sub_claim.expected_value {
+            return Err(VerificationError::FinalEvaluationMismatch {
+                expected: evaluation,
+                received: sub_claim.expected_value,
+            });
         }
+        Ok(())
+    }
+}
 
-        println!("challenges: {:?}", challenges);
-        println!("claimed sums: {}", claimed_sum);
-        println!(
-            "clameddd sum: {:?}",
-            self.original_poly.evaluate(&challenges)
-        );
+// What the caller still has to check: the polynomial evaluates to expected_value at point
+// (e.g. with a polynomial commitment opening)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubClaim<F: Field> {
+    pub point: Vec<F>,
+    pub expected_value: F,
+}
+
+// Succinct verifier: only needs the number of variables and the claimed sum, never the
+// polynomial itself. The round polynomials of a multilinear sumcheck are linear, so there is
+// no separate degree bound to pass in. The transcript must be in the same state as the one
+// given to prover::prove.
+pub fn verify<F: PrimeField, K: HashTrait>(
+    proof: &Proof<F>,
+    no_of_variables: usize,
+    claimed_sum: F,
+    transcript: &mut Transcript<K, F>,
+) -> Result<SubClaim<F>, VerificationError<F>> {
+    check_proof_shape(
+        proof.claimed_sum,
+        proof.round_polys.len(),
+        no_of_variables,
+        claimed_sum,
+    )?;
+
+    verify_rounds(claimed_sum, no_of_variables, transcript, |round, _| {
+        proof.round_polys[round]
+    })
+}
 
-        if claimed_sum != self.original_poly.evaluate(&challenges) {
-            return false;
+// Same checks as verify, rebuilding g(1) = claim - g(0) for every round. The rebuilt round
+// polynomial is what goes into the transcript, so both encodings draw the same challenges.
+pub fn verify_compressed<F: PrimeField, K: HashTrait>(
+    proof: &CompressedProof<F>,
+    no_of_variables: usize,
+    claimed_sum: F,
+    transcript: &mut Transcript<K, F>,
+) -> Result<SubClaim<F>, VerificationError<F>> {
+    check_proof_shape(
+        proof.claimed_sum,
+        proof.round_polys.len(),
+        no_of_variables,
+        claimed_sum,
+    )?;
+
+    verify_rounds(claimed_sum, no_of_variables, transcript, |round, claim| {
+        let eval_at_zero = proof.round_polys[round];
+        [eval_at_zero, claim - eval_at_zero]
+    })
+}
+
+pub(crate) fn check_proof_shape<F: Field>(
+    proof_claimed_sum: F,
+    no_of_rounds: usize,
+    no_of_variables: usize,
+    claimed_sum: F,
+) -> Result<(), VerificationError<F>> {
+    if proof_claimed_sum != claimed_sum {
+        return Err(VerificationError::ClaimedSumMismatch {
+            expected: claimed_sum,
+            received: proof_claimed_sum,
+        });
+    }
+    if no_of_rounds != no_of_variables {
+        return Err(VerificationError::MalformedProof {
+            field: "round_polys",
+            expected: no_of_variables,
+            received: no_of_rounds,
+        });
+    }
+    Ok(())
+}
+
+fn verify_rounds<F: PrimeField, K: HashTrait>(
+    claimed_sum: F,
+    no_of_variables: usize,
+    transcript: &mut Transcript<K, F>,
+    round_poly_at: impl Fn(usize, F) -> [F; 2],
+) -> Result<SubClaim<F>, VerificationError<F>> {
+    transcript.append_field(b"claimed_sum", &claimed_sum);
+    let mut claimed_sum = claimed_sum;
+    let mut challenges = Vec::with_capacity(no_of_variables);
+
+    for round in 0..no_of_variables {
+        let round_poly = round_poly_at(round, claimed_sum);
+        let round_sum: F = round_poly.iter().sum();
+        if claimed_sum != round_sum {
+            return Err(VerificationError::RoundSumMismatch {
+                round,
+                expected: claimed_sum,
+                received: round_sum,
+            });
         }
+        transcript.append_point(b"round_poly", &round_poly);
+        let challenge: F = transcript.challenge_scalar(b"challenge");
+        claimed_sum = round_poly[0] + challenge * (round_poly[1] - round_poly[0]);
+        challenges.push(challenge);
+    }
+
+    Ok(SubClaim {
+        point: challenges,
+        expected_value: claimed_sum,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::prove;
+    use ark_bn254::Fq;
+
+    fn to_field(input: Vec<u64>) -> Vec<Fq> {
+        input.into_iter().map(Fq::from).collect()
+    }
+
+    #[test]
+    fn test_succinct_verify() {
+        let poly = MultilinearPolynomial::new(to_field(vec![0, 0, 0, 3, 0, 0, 2, 5]));
+
+        // stands in for a commitment to the polynomial
+        let mut prover_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"sumcheck");
+        prover_transcript.append_message(b"commitment", b"poly");
+        let proof = prove(&poly, Fq::from(10), &mut prover_transcript);
+
+        let mut verifier_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"sumcheck");
+        verifier_transcript.append_message(b"commitment", b"poly");
+        let sub_claim = verify(&proof, 3, Fq::from(10), &mut verifier_transcript)
+            .expect("sumcheck should verify");
+
+        assert_eq!(poly.evaluate(&sub_claim.point), sub_claim.expected_value);
+    }
+
+    #[test]
+    fn test_succinct_verify_rejects_wrong_sum() {
+        let poly = MultilinearPolynomial::new(to_field(vec![0, 0, 0, 3, 0, 0, 2, 5]));
+
+        let mut prover_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"sumcheck");
+        let proof = prove(&poly, Fq::from(11), &mut prover_transcript);
+
+        let mut verifier_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"sumcheck");
+        assert_eq!(
+            verify(&proof, 3, Fq::from(11), &mut verifier_transcript),
+            Err(VerificationError::RoundSumMismatch {
+                round: 0,
+                expected: Fq::from(11),
+                received: Fq::from(10),
+            })
+        );
+    }
+
+    #[test]
+    fn test_compressed_proof() {
+        let eval_points = to_field(vec![0, 0, 0, 3, 0, 0, 2, 5]);
+        let proof = crate::prover::Prover::new(&eval_points, Fq::from(10)).prove();
+        let compressed = proof.compress();
+        assert_eq!(compressed.round_polys.len(), proof.round_polys.len());
+
+        assert!(Verify::new(&eval_points)
+            .verify_compressed(compressed.clone())
+            .is_ok());
 
-        true
+        // a tampered g(0) shifts the rebuilt g(1) and the final check catches it
+        let mut tampered = compressed;
+        tampered.round_polys[1] += Fq::from(1);
+        assert!(matches!(
+            Verify::new(&eval_points).verify_compressed(tampered),
+            Err(VerificationError::FinalEvaluationMismatch { .. })
+        ));
     }
 }
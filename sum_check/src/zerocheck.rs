@@ -0,0 +1,242 @@
+use crate::{
+    error::VerificationError,
+    transcript::{HashTrait, Transcript},
+};
+use ark_ff::PrimeField;
+use multivariate_poly::{eq_evaluate, eq_polynomial, product_poly::ProductPoly, sum_poly::SumPoly};
+use univariate_poly::UnivariatePolynomial;
+
+// Zero-check: proves f(x) = 0 for every x on the boolean hypercube by running sumcheck on
+// f(x) * eq(x, r) with claimed sum zero, where r is squeezed from the transcript.
+// f is given as a SumPoly (sum of products of multilinear polynomials).
+
+#[derive(Debug, Clone)]
+pub struct ZeroCheckProof<F: PrimeField> {
+    pub round_polys: Vec<UnivariatePolynomial<F>>,
+    // evaluations of every factor of f at the final sumcheck point, shaped like f's products
+    pub poly_evals: Vec<Vec<F>>,
+}
+
+// What the caller still has to check: every factor poly_evals[i][j] of f must open to
+// that value at `point` (e.g. with a polynomial commitment opening)
+#[derive(Debug, Clone)]
+pub struct ZeroCheckSubClaim<F: PrimeField> {
+    pub point: Vec<F>,
+    pub poly_evals: Vec<Vec<F>>,
+}
+
+pub fn prove<F: PrimeField, K: HashTrait>(
+    poly: &SumPoly<F>,
+    transcript: &mut Transcript<K, F>,
+) -> ZeroCheckProof<F> {
+    let no_of_variables = poly.no_of_variables();
+    let r = transcript.challenge_scalars(b"zerocheck_r", no_of_variables);
+    let eq_poly = eq_polynomial(&r);
+
+    // f(x) * eq(x, r): append eq to every product term
+    let mut current_poly = SumPoly::new(
+        poly.product_polys
+            .iter()
+            .map(|product| {
+                let mut factors = product.poly_coefficients.clone();
+                factors.push(eq_poly.clone());
+                ProductPoly::new(factors)
+            })
+            .collect(),
+    );
+    let degree = current_poly.degree();
+
+    let mut round_polys = Vec::with_capacity(no_of_variables);
+    let mut challenges = Vec::with_capacity(no_of_variables);
+
+    for _ in 0..no_of_variables {
+        let points: Vec<(F, F)> = (0..=degree)
+            .map(|i| {
+                let x = F::from(i as u64);
+                (x, hypercube_sum(&current_poly.partial_evaluate(0, x)))
+            })
+            .collect();
+        let round_poly = UnivariatePolynomial::interpolate(points);
+
+        transcript.append_point(b"round_poly", &round_poly.coefficients);
+        round_polys.push(round_poly);
+
+        let challenge: F = transcript.challenge_scalar(b"challenge");
+        current_poly = current_poly.partial_evaluate(0, challenge);
+        challenges.push(challenge);
+    }
+
+    let poly_evals = poly
+        .product_polys
+        .iter()
+        .map(|product| {
+            product
+                .poly_coefficients
+                .iter()
+                .map(|factor| factor.evaluate(&challenges))
+                .collect()
+        })
+        .collect();
+
+    ZeroCheckProof {
+        round_polys,
+        poly_evals,
+    }
+}
+
+// `degree` is the degree of f in each variable (the number of factors per product term)
+pub fn verify<F: PrimeField, K: HashTrait>(
+    proof: &ZeroCheckProof<F>,
+    no_of_variables: usize,
+    degree: usize,
+    transcript: &mut Transcript<K, F>,
+) -> Result<ZeroCheckSubClaim<F>, VerificationError<F>> {
+    if proof.round_polys.len() != no_of_variables {
+        return Err(VerificationError::MalformedProof {
+            field: "round_polys",
+            expected: no_of_variables,
+            received: proof.round_polys.len(),
+        });
+    }
+
+    let r = transcript.challenge_scalars(b"zerocheck_r", no_of_variables);
+
+    let mut claimed_sum = F::zero();
+    let mut challenges = Vec::with_capacity(no_of_variables);
+
+    for (round, round_poly) in proof.round_polys.iter().enumerate() {
+        // multiplying by eq raises the degree by one
+        if round_poly.coefficients.len() > degree + 2 {
+            return Err(VerificationError::RoundDegreeTooHigh {
+                round,
+                max_degree: degree + 1,
+                received: round_poly.coefficients.len() - 1,
+            });
+        }
+        let round_sum = round_poly.evaluate(F::zero()) + round_poly.evaluate(F::one());
+        if round_sum != claimed_sum {
+            return Err(VerificationError::RoundSumMismatch {
+                round,
+                expected: claimed_sum,
+                received: round_sum,
+            });
+        }
+
+        transcript.append_point(b"round_poly", &round_poly.coefficients);
+        let challenge: F = transcript.challenge_scalar(b"challenge");
+
+        claimed_sum = round_poly.evaluate(challenge);
+        challenges.push(challenge);
+    }
+
+    let f_eval: F = proof
+        .poly_evals
+        .iter()
+        .map(|factors| factors.iter().product::<F>())
+        .sum();
+
+    let final_eval = f_eval * eq_evaluate(&challenges, &r);
+    if final_eval != claimed_sum {
+        return Err(VerificationError::FinalEvaluationMismatch {
+            expected: claimed_sum,
+            received: final_eval,
+        });
+    }
+
+    Ok(ZeroCheckSubClaim {
+        point: challenges,
+        poly_evals: proof.poly_evals.clone(),
+    })
+}
+
+fn hypercube_sum<F: PrimeField>(poly: &SumPoly<F>) -> F {
+    poly.product_polys
+        .iter()
+        .map(|product| {
+            let size = product.poly_coefficients[0].coefficients.len();
+            (0..size)
+                .map(|i| {
+                    product
+                        .poly_coefficients
+                        .iter()
+                        .map(|factor| factor.coefficients[i])
+                        .product::<F>()
+                })
+                .sum::<F>()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fq;
+    use multivariate_poly::MultilinearPolynomial;
+    use sha3::Keccak256;
+
+    fn to_field(input: Vec<i64>) -> Vec<Fq> {
+        input.into_iter().map(Fq::from).collect()
+    }
+
+    // a * b - c as a SumPoly: [a, b] + [-c, 1]
+    fn gate_poly(a: Vec<i64>, b: Vec<i64>, c: Vec<i64>) -> SumPoly<Fq> {
+        let ones = vec![1; c.len()];
+        let neg_c = c.into_iter().map(|v| -v).collect();
+        SumPoly::new(vec![
+            ProductPoly::new(vec![
+                MultilinearPolynomial::new(to_field(a)),
+                MultilinearPolynomial::new(to_field(b)),
+            ]),
+            ProductPoly::new(vec![
+                MultilinearPolynomial::new(to_field(neg_c)),
+                MultilinearPolynomial::new(to_field(ones)),
+            ]),
+        ])
+    }
+
+    #[test]
+    fn test_zerocheck() {
+        let poly = gate_poly(
+            vec![1, 2, 3, 4, 5, 6, 7, 8],
+            vec![2, 2, 2, 2, 3, 3, 3, 3],
+            vec![2, 4, 6, 8, 15, 18, 21, 24],
+        );
+
+        let mut prover_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"zerocheck");
+        let proof = prove(&poly, &mut prover_transcript);
+
+        let mut verifier_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"zerocheck");
+        let sub_claim = verify(&proof, 3, poly.degree(), &mut verifier_transcript)
+            .expect("zerocheck should verify");
+
+        // discharge the sub-claims against the actual polynomials
+        for (product, evals) in poly.product_polys.iter().zip(sub_claim.poly_evals.iter()) {
+            for (factor, eval) in product.poly_coefficients.iter().zip(evals.iter()) {
+                assert_eq!(factor.evaluate(&sub_claim.point), *eval);
+            }
+        }
+    }
+
+    #[test]
+    fn test_zerocheck_rejects_non_zero_poly() {
+        // last gate is wrong: 8 * 3 != 25
+        let poly = gate_poly(
+            vec![1, 2, 3, 4, 5, 6, 7, 8],
+            vec![2, 2, 2, 2, 3, 3, 3, 3],
+            vec![2, 4, 6, 8, 15, 18, 21, 25],
+        );
+
+        let mut prover_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"zerocheck");
+        let proof = prove(&poly, &mut prover_transcript);
+
+        let mut verifier_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"zerocheck");
+        assert!(matches!(
+            verify(&proof, 3, poly.degree(), &mut verifier_transcript),
+            Err(VerificationError::RoundSumMismatch { round: 0, .. })
+        ));
+    }
+}
@@ -0,0 +1,288 @@
+use crate::{
+    error::VerificationError,
+    prover::{self, Proof},
+    transcript::{HashTrait, Transcript},
+    verifier,
+};
+use ark_ff::PrimeField;
+use ark_std::rand::Rng;
+use multivariate_poly::MultilinearPolynomial;
+use univariate_poly::UnivariatePolynomial;
+
+// Zero-knowledge sumcheck in the style of Libra: the prover picks a random masking polynomial
+// g(x) = c + g_1(x_1) + ... + g_n(x_n), sends its sum G, and the sumcheck is run on p + ρ * g
+// with ρ squeezed from the transcript after both sums are bound. The round messages are then
+// masked by g's random coefficients instead of leaking combinations of p.
+// The caller has to bind g beforehand (e.g. by committing to it) and discharge two claims at
+// the end: p(point) and g(point) = masking_eval.
+
+#[derive(Debug, Clone)]
+pub struct MaskingPolynomial<F: PrimeField> {
+    pub constant: F,
+    // g_i, one univariate per variable, ascending coefficients
+    pub univariates: Vec<UnivariatePolynomial<F>>,
+}
+
+impl<F: PrimeField> MaskingPolynomial<F> {
+    pub fn new(constant: F, univariates: Vec<UnivariatePolynomial<F>>) -> Self {
+        Self {
+            constant,
+            univariates,
+        }
+    }
+
+    // `degree` has to match the degree of the round polynomials it masks
+    pub fn rand<R: Rng>(no_of_variables: usize, degree: usize, rng: &mut R) -> Self {
+        Self {
+            constant: F::rand(rng),
+            univariates: (0..no_of_variables)
+                .map(|_| UnivariatePolynomial::new((0..=degree).map(|_| F::rand(rng)).collect()))
+                .collect(),
+        }
+    }
+
+    pub fn no_of_variables(&self) -> usize {
+        self.univariates.len()
+    }
+
+    // every g_i(x_i) shows up for half of the 2^n points with x_i = 0 and half with x_i = 1
+    pub fn sum(&self) -> F {
+        let n = self.no_of_variables();
+        if n == 0 {
+            return self.constant;
+        }
+        let half_size = F::from(1u64 << (n - 1));
+        let univariate_sums: F = self
+            .univariates
+            .iter()
+            .map(|g| g.evaluate(F::zero()) + g.evaluate(F::one()))
+            .sum();
+        half_size * (self.constant.double() + univariate_sums)
+    }
+
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(
+            point.len(),
+            self.no_of_variables(),
+            "wrong number of variables"
+        );
+        self.constant
+            + self
+                .univariates
+                .iter()
+                .zip(point.iter())
+                .map(|(g, x)| g.evaluate(*x))
+                .sum::<F>()
+    }
+
+    // the sumcheck round polynomial of g once the first challenges.len() variables are bound
+    pub fn round_poly(&self, challenges: &[F]) -> UnivariatePolynomial<F> {
+        let n = self.no_of_variables();
+        let round = challenges.len();
+        assert!(round < n, "every variable is already bound");
+
+        // number of points of the hypercube left after the current variable
+        let remaining_size = F::from(1u64 << (n - round - 1));
+        let bound: F = self.constant
+            + self.univariates[..round]
+                .iter()
+                .zip(challenges.iter())
+                .map(|(g, r)| g.evaluate(*r))
+                .sum::<F>();
+        let free: F = self.univariates[round + 1..]
+            .iter()
+            .map(|g| g.evaluate(F::zero()) + g.evaluate(F::one()))
+            .sum();
+        let half = F::from(2u64).inverse().unwrap();
+
+        let mut coefficients: Vec<F> = self.univariates[round]
+            .coefficients
+            .iter()
+            .map(|coeff| remaining_size * coeff)
+            .collect();
+        coefficients[0] += remaining_size * (bound + half * free);
+        UnivariatePolynomial::new(coefficients)
+    }
+
+    // g over the hypercube, for masks that are multilinear (degree 1)
+    fn evaluations(&self) -> Vec<F> {
+        self.univariates
+            .iter()
+            .fold(vec![self.constant], |table, g| {
+                let (at_zero, at_one) = (g.evaluate(F::zero()), g.evaluate(F::one()));
+                table
+                    .into_iter()
+                    .flat_map(|value| [value + at_zero, value + at_one])
+                    .collect()
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ZkProof<F: PrimeField> {
+    pub claimed_sum: F,
+    pub masking_sum: F,
+    pub round_polys: Vec<[F; 2]>,
+    pub masking_eval: F,
+}
+
+// p(point) == expected_value and g(point) == masking_eval are left to the caller
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZkSubClaim<F: PrimeField> {
+    pub point: Vec<F>,
+    pub expected_value: F,
+    pub masking_eval: F,
+}
+
+// binds both sums and squeezes the coefficient ρ that mixes the mask in
+pub fn masking_coefficient<F: PrimeField, K: HashTrait>(
+    claimed_sum: F,
+    masking_sum: F,
+    transcript: &mut Transcript<K, F>,
+) -> F {
+    transcript.append_field(b"claimed_sum", &claimed_sum);
+    transcript.append_field(b"masking_sum", &masking_sum);
+    transcript.challenge_scalar(b"masking_coefficient")
+}
+
+// The mask has to be multilinear here so the round polynomials stay linear
+pub fn prove<F: PrimeField, K: HashTrait>(
+    poly: &MultilinearPolynomial<F>,
+    claimed_sum: F,
+    mask: &MaskingPolynomial<F>,
+    transcript: &mut Transcript<K, F>,
+) -> ZkProof<F> {
+    assert_eq!(
+        mask.no_of_variables(),
+        poly.no_of_variables(),
+        "the mask must have as many variables as the polynomial"
+    );
+    assert!(
+        mask.univariates.iter().all(|g| g.coefficients.len() <= 2),
+        "the mask must be multilinear"
+    );
+
+    let masking_sum = mask.sum();
+    let rho = masking_coefficient(claimed_sum, masking_sum, transcript);
+
+    let masked_table = poly
+        .coefficients
+        .iter()
+        .zip(mask.evaluations())
+        .map(|(p, g)| *p + rho * g)
+        .collect();
+    let (proof, point) =
+        prover::prove_rounds(masked_table, claimed_sum + rho * masking_sum, transcript);
+
+    ZkProof {
+        claimed_sum,
+        masking_sum,
+        round_polys: proof.round_polys,
+        masking_eval: mask.evaluate(&point),
+    }
+}
+
+pub fn verify<F: PrimeField, K: HashTrait>(
+    proof: &ZkProof<F>,
+    no_of_variables: usize,
+    claimed_sum: F,
+    transcript: &mut Transcript<K, F>,
+) -> Result<ZkSubClaim<F>, VerificationError<F>> {
+    if proof.claimed_sum != claimed_sum {
+        return Err(VerificationError::ClaimedSumMismatch {
+            expected: claimed_sum,
+            received: proof.claimed_sum,
+        });
+    }
+
+    let rho = masking_coefficient(claimed_sum, proof.masking_sum, transcript);
+    let masked_sum = claimed_sum + rho * proof.masking_sum;
+    let masked_proof = Proof {
+        claimed_sum: masked_sum,
+        round_polys: proof.round_polys.clone(),
+    };
+    let sub_claim = verifier::verify(&masked_proof, no_of_variables, masked_sum, transcript)?;
+
+    Ok(ZkSubClaim {
+        point: sub_claim.point,
+        expected_value: sub_claim.expected_value - rho * proof.masking_eval,
+        masking_eval: proof.masking_eval,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fq;
+    use ark_std::test_rng;
+    use sha3::Keccak256;
+
+    fn to_field(input: Vec<u64>) -> Vec<Fq> {
+        input.into_iter().map(Fq::from).collect()
+    }
+
+    #[test]
+    fn test_masking_polynomial() {
+        let mask = MaskingPolynomial::<Fq>::rand(3, 1, &mut test_rng());
+        let table = MultilinearPolynomial::new(mask.evaluations());
+        assert_eq!(mask.sum(), table.coefficients.iter().sum());
+
+        let point = to_field(vec![3, 5, 7]);
+        assert_eq!(mask.evaluate(&point), table.evaluate(&point));
+
+        // round polynomial after binding the first variable
+        let round_poly = mask.round_poly(&point[..1]);
+        let folded = table.partial_evaluate(0, point[0]);
+        let half = folded.coefficients.len() / 2;
+        assert_eq!(
+            round_poly.evaluate(Fq::from(0)),
+            folded.coefficients[..half].iter().sum()
+        );
+        assert_eq!(
+            round_poly.evaluate(Fq::from(1)),
+            folded.coefficients[half..].iter().sum()
+        );
+    }
+
+    #[test]
+    fn test_zk_sumcheck() {
+        let poly = MultilinearPolynomial::new(to_field(vec![0, 0, 0, 3, 0, 0, 2, 5]));
+        let mask = MaskingPolynomial::rand(3, 1, &mut test_rng());
+
+        let mut prover_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"zk_sumcheck");
+        let proof = prove(&poly, Fq::from(10), &mask, &mut prover_transcript);
+
+        // the masked round messages differ from the plain ones
+        let mut plain_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"zk_sumcheck");
+        let plain_proof = prover::prove(&poly, Fq::from(10), &mut plain_transcript);
+        assert_ne!(proof.round_polys[0], plain_proof.round_polys[0]);
+
+        let mut verifier_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"zk_sumcheck");
+        let sub_claim = verify(&proof, 3, Fq::from(10), &mut verifier_transcript)
+            .expect("zk sumcheck should verify");
+
+        assert_eq!(poly.evaluate(&sub_claim.point), sub_claim.expected_value);
+        assert_eq!(mask.evaluate(&sub_claim.point), sub_claim.masking_eval);
+    }
+
+    #[test]
+    fn test_zk_sumcheck_rejects_wrong_masking_sum() {
+        let poly = MultilinearPolynomial::new(to_field(vec![0, 0, 0, 3, 0, 0, 2, 5]));
+        let mask = MaskingPolynomial::rand(3, 1, &mut test_rng());
+
+        let mut prover_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"zk_sumcheck");
+        let mut proof = prove(&poly, Fq::from(10), &mask, &mut prover_transcript);
+        proof.masking_sum += Fq::from(1);
+
+        let mut verifier_transcript =
+            Transcript::<Keccak256, Fq>::init(Keccak256::default(), b"zk_sumcheck");
+        assert!(matches!(
+            verify(&proof, 3, Fq::from(10), &mut verifier_transcript),
+            Err(VerificationError::RoundSumMismatch { round: 0, .. })
+        ));
+    }
+}